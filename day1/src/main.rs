@@ -1,42 +1,281 @@
-#![feature(exclusive_range_pattern)]
-#![feature(let_chains)]
-
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
 use std::fs::read_to_string;
+use std::io;
+use std::ops::AddAssign;
+use std::str::FromStr;
 
 fn main() {
-    // read from input.txt and call extract_calories_per_elf
-    let input_contents = read_to_string("./input/calories.txt").expect("Unable to read input.txt");
-    let mut elf_calories = extract_calories_per_elf(input_contents.as_str());
+    // read every file given as argument (or input/calories.txt) and call extract_calories_per_elf
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let paths = if args.is_empty() {
+        vec!["./input/calories.txt"]
+    } else {
+        args.iter().map(|arg| arg.as_str()).collect()
+    };
+    let elf_calories = extract_calories_from_files(&paths).expect("Unable to read input files");
+
+    println!("{}", calorie_report(&elf_calories));
+}
 
-    elf_calories.sort_by(|a, b| b.cmp(a));
-    let top_three = elf_calories.into_iter().take(3).collect::<Vec<_>>();
-    println!(
-        "Top 3 most-economic elves: {:?}.\nTotal calories collected: {}",
-        &top_three,
-        top_three.iter().sum::<i32>()
-    );
+#[allow(dead_code)]
+fn format_ranking(totals: &[i32]) -> String {
+    totals
+        .iter()
+        .enumerate()
+        .map(|(i, total)| format!("{}) {}", i + 1, total))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    raw: String,
 }
 
 fn extract_calories_per_elf(calories_notes: &str) -> Vec<i32> {
-    let mut elf_calories = Vec::new();
-    let mut elf_calorie = 0;
+    group_calories_per_elf::<i32>(calories_notes)
+        .unwrap_or_else(|err| panic!("Unable to parse line into i32 {}", err.raw))
+}
+
+#[allow(dead_code)]
+fn extract_calories_per_elf_f64(calories_notes: &str) -> Result<Vec<f64>, ParseError> {
+    group_calories_per_elf::<f64>(calories_notes)
+}
 
+// Same as `extract_calories_per_elf`, but a `continuation_marker` line glues the block that
+// follows it back onto the previous elf, undoing a stray blank line.
+#[allow(dead_code)]
+fn extract_calories_per_elf_continued(calories_notes: &str, continuation_marker: &str) -> Vec<i32> {
+    group_calories_per_elf_with_marker::<i32>(calories_notes, Some(continuation_marker))
+        .unwrap_or_else(|err| panic!("Unable to parse line into i32 {}", err.raw))
+}
+
+// Each elf starts with a header line like `Elf A:` and carries the sum of the lines below it.
+// Blank lines don't matter here. Lines before the first header go to an elf with an empty name.
+#[allow(dead_code)]
+fn extract_named_elves(calories_notes: &str) -> Vec<(String, i32)> {
+    let mut named_elves: Vec<(String, i32)> = Vec::new();
     for line in calories_notes.lines() {
         let sanitized_line = line.trim();
         if sanitized_line.is_empty() {
+            continue;
+        }
+        if let Some(name) = sanitized_line.strip_suffix(':') {
+            named_elves.push((name.trim().to_string(), 0));
+            continue;
+        }
+        let calories = sanitized_line
+            .parse::<i32>()
+            .unwrap_or_else(|_| panic!("Unable to parse line into i32 {}", line));
+        match named_elves.last_mut() {
+            Some((_, elf_calorie)) => *elf_calorie += calories,
+            None => named_elves.push((String::new(), calories)),
+        }
+    }
+    named_elves
+}
+
+// Elves are separated by blank lines, and each one carries the sum of the lines above it.
+fn group_calories_per_elf<T>(calories_notes: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr + AddAssign + PartialOrd + Default + Copy,
+{
+    group_calories_per_elf_with_marker(calories_notes, None)
+}
+
+fn group_calories_per_elf_with_marker<T>(
+    calories_notes: &str,
+    continuation_marker: Option<&str>,
+) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr + AddAssign + PartialOrd + Default + Copy,
+{
+    let mut elf_calories = Vec::new();
+    let mut elf_calorie = T::default();
+
+    for (i, line) in calories_notes.lines().enumerate() {
+        let sanitized_line = line.trim();
+        if Some(sanitized_line) == continuation_marker {
+            // Keep accumulating on top of the previous elf rather than starting a new one.
+            if let Some(previous_elf_calorie) = elf_calories.pop() {
+                elf_calorie += previous_elf_calorie;
+            }
+        } else if sanitized_line.is_empty() {
             elf_calories.push(elf_calorie);
-            elf_calorie = 0;
+            elf_calorie = T::default();
         } else {
-            elf_calorie += sanitized_line
-                .parse::<i32>()
-                .expect(format!("Unable to parse line into i32 {}", line).as_str());
+            elf_calorie += sanitized_line.parse::<T>().map_err(|_| ParseError {
+                line: i + 1,
+                raw: line.to_string(),
+            })?;
         }
     }
 
-    if elf_calorie > 0 {
+    if elf_calorie > T::default() {
         elf_calories.push(elf_calorie);
     }
+    Ok(elf_calories)
+}
+
+// Grand total of every note, ignoring how they are grouped into elves.
+#[allow(dead_code)]
+fn total_calories(calories_notes: &str) -> Result<i32, ParseError> {
+    let mut total = 0;
+    for (i, line) in calories_notes.lines().enumerate() {
+        let sanitized_line = line.trim();
+        if !sanitized_line.is_empty() {
+            total += sanitized_line.parse::<i32>().map_err(|_| ParseError {
+                line: i + 1,
+                raw: line.to_string(),
+            })?;
+        }
+    }
+    Ok(total)
+}
+
+// An elf's total, along with its position in the notes so that ties can be broken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Elf {
+    index: usize,
+    calories: i32,
+}
+
+fn top_n_calories(elf_calories: &[i32], n: usize) -> Vec<i32> {
+    top_n_elves(elf_calories, n)
+        .into_iter()
+        .map(|elf| elf.calories)
+        .collect()
+}
+
+// Most calories first. Ties go to the earlier elf.
+fn top_n_elves(elf_calories: &[i32], n: usize) -> Vec<Elf> {
+    let mut elves = elf_calories
+        .iter()
+        .enumerate()
+        .map(|(index, &calories)| Elf { index, calories })
+        .collect::<Vec<Elf>>();
+    elves.sort_by(|a, b| b.calories.cmp(&a.calories).then(a.index.cmp(&b.index)));
+    elves.into_iter().take(n).collect()
+}
+
+// Every elf in the notes, ranked the same way as `top_n_elves`.
+#[allow(dead_code)]
+fn rank_elves(calories_notes: &str) -> Vec<Elf> {
+    let elf_calories = extract_calories_per_elf(calories_notes);
+    top_n_elves(&elf_calories, elf_calories.len())
+}
+
+#[allow(dead_code)]
+fn top_n_heap(elf_calories: impl IntoIterator<Item = i32>, n: usize) -> Vec<i32> {
+    top_n_elves_heap(elf_calories, n)
+        .into_iter()
+        .map(|elf| elf.calories)
+        .collect()
+}
+
+#[allow(dead_code)]
+fn top_n_elves_heap(elf_calories: impl IntoIterator<Item = i32>, n: usize) -> Vec<Elf> {
+    // A min-heap holding the n best elves seen so far: memory stays O(n) no matter how many elves.
+    // Elves rank by calories, then by earliest index, hence the inner `Reverse` on the index.
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for (index, calories) in elf_calories.into_iter().enumerate() {
+        heap.push(Reverse((calories, Reverse(index))));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
+    // Sorting `Reverse` ascending yields the best elves first.
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((calories, Reverse(index)))| Elf { index, calories })
+        .collect()
+}
+
+// How far the richest elf sits above the mean. None when there are no elves.
+#[allow(dead_code)]
+fn top_elf_excess(elf_calories: &[i32]) -> Option<f64> {
+    let max = *elf_calories.iter().max()?;
+    let mean = elf_calories.iter().sum::<i32>() as f64 / elf_calories.len() as f64;
+    Some(max as f64 - mean)
+}
+
+// Largest total in each run of `window` consecutive elves.
+// Empty when the window is zero or wider than the amount of elves, as there's no full window then.
+#[allow(dead_code)]
+fn max_in_windows(elf_calories: &[i32], window: usize) -> Vec<i32> {
+    if window == 0 {
+        return Vec::new();
+    }
     elf_calories
+        .windows(window)
+        .map(|elves| *elves.iter().max().expect("Windows are never empty"))
+        .collect()
+}
+
+// Summary of a whole run of notes.
+#[derive(Debug, PartialEq)]
+struct CalorieReport {
+    elves: usize,
+    // None when there are no elves at all.
+    top_elf: Option<Elf>,
+    top_three_total: i32,
+    mean: f64,
+}
+
+impl fmt::Display for CalorieReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Elves: {}", self.elves)?;
+        match self.top_elf {
+            Some(elf) => writeln!(f, "Top elf: #{} with {}", elf.index + 1, elf.calories)?,
+            None => writeln!(f, "Top elf: none")?,
+        }
+        writeln!(f, "Top 3 total: {}", self.top_three_total)?;
+        write!(f, "Mean: {:.2}", self.mean)
+    }
+}
+
+#[allow(dead_code)]
+fn report(calories_notes: &str) -> Result<CalorieReport, ParseError> {
+    Ok(calorie_report(&group_calories_per_elf::<i32>(
+        calories_notes,
+    )?))
+}
+
+fn calorie_report(elf_calories: &[i32]) -> CalorieReport {
+    let mean = if elf_calories.is_empty() {
+        0.0
+    } else {
+        elf_calories.iter().sum::<i32>() as f64 / elf_calories.len() as f64
+    };
+    CalorieReport {
+        elves: elf_calories.len(),
+        top_elf: top_n_elves(elf_calories, 1).first().copied(),
+        top_three_total: top_n_calories(elf_calories, 3).iter().sum(),
+        mean,
+    }
+}
+
+fn extract_calories_from_files(paths: &[&str]) -> io::Result<Vec<i32>> {
+    // Each file holds its own list of elves, so we append rather than merge them.
+    let mut elf_calories = Vec::new();
+    for path in paths {
+        let calories_notes = read_to_string(path)?;
+        let file_calories =
+            group_calories_per_elf::<i32>(calories_notes.as_str()).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Unable to parse line {} of {} into i32: {}",
+                        err.line, path, err.raw
+                    ),
+                )
+            })?;
+        elf_calories.extend(file_calories);
+    }
+    Ok(elf_calories)
 }
 
 #[cfg(test)]
@@ -79,4 +318,337 @@ mod test_extract_calories_per_elf {
         let elf_calories = extract_calories_per_elf(calories_notes);
         assert_eq!(elf_calories, vec![1265, 350, 300, 500, 330]);
     }
+
+    #[test]
+    fn total_matches_sum_of_elves() {
+        let calories_notes = "10
+    900
+
+    100
+    200
+
+
+    300
+    ";
+        let elf_calories = extract_calories_per_elf(calories_notes);
+        assert_eq!(total_calories(calories_notes), Ok(1510));
+        assert_eq!(
+            total_calories(calories_notes),
+            Ok(elf_calories.iter().sum::<i32>())
+        );
+    }
+
+    #[test]
+    fn total_with_invalid_value() {
+        let calories_notes = "10\n\nten";
+        assert_eq!(
+            total_calories(calories_notes),
+            Err(ParseError {
+                line: 3,
+                raw: "ten".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn with_continuation_marker() {
+        let calories_notes = "100
+    200
+
+    ...continued
+    300
+
+    400
+
+    ...continued
+    50";
+        assert_eq!(
+            extract_calories_per_elf_continued(calories_notes, "...continued"),
+            vec![600, 450]
+        );
+        assert_eq!(
+            extract_calories_per_elf(
+                "100
+    200
+
+    300"
+            ),
+            vec![300, 300]
+        );
+    }
+
+    #[test]
+    fn with_named_elves() {
+        let calories_notes = "Elf A:
+    100
+    400
+
+    Elf B:
+    300
+    Elf C:
+    ";
+        assert_eq!(
+            extract_named_elves(calories_notes),
+            vec![
+                ("Elf A".to_string(), 500),
+                ("Elf B".to_string(), 300),
+                ("Elf C".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_decimal_values() {
+        let calories_notes = "120.5
+    79.5
+
+    0.25
+    0.5
+
+    10";
+        let elf_calories = extract_calories_per_elf_f64(calories_notes);
+        assert_eq!(elf_calories, Ok(vec![200.0, 0.75, 10.0]));
+    }
+
+    #[test]
+    fn with_invalid_decimal_value() {
+        let calories_notes = "120.5
+
+    12,5";
+        let elf_calories = extract_calories_per_elf_f64(calories_notes);
+        assert_eq!(
+            elf_calories,
+            Err(ParseError {
+                line: 3,
+                raw: "    12,5".to_string()
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_extract_calories_from_files {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::{remove_file, write};
+
+    #[test]
+    fn appends_elves_across_files() {
+        let first_path = temp_dir().join(format!("day1-{}-first.txt", std::process::id()));
+        let second_path = temp_dir().join(format!("day1-{}-second.txt", std::process::id()));
+        write(&first_path, "100\n400\n\n50\n").unwrap();
+        write(&second_path, "200\n\n300\n100").unwrap();
+
+        let elf_calories = extract_calories_from_files(&[
+            first_path.to_str().unwrap(),
+            second_path.to_str().unwrap(),
+        ]);
+        remove_file(&first_path).unwrap();
+        remove_file(&second_path).unwrap();
+
+        assert_eq!(elf_calories.unwrap(), vec![500, 50, 200, 400]);
+    }
+
+    #[test]
+    fn invalid_file() {
+        let invalid_path = temp_dir().join(format!("day1-{}-invalid.txt", std::process::id()));
+        write(&invalid_path, "100\n\nabc\n").unwrap();
+
+        let elf_calories = extract_calories_from_files(&[invalid_path.to_str().unwrap()]);
+        remove_file(&invalid_path).unwrap();
+
+        assert_eq!(elf_calories.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn missing_file() {
+        let missing_path = temp_dir().join(format!("day1-{}-missing.txt", std::process::id()));
+        let elf_calories = extract_calories_from_files(&[missing_path.to_str().unwrap()]);
+        assert_eq!(elf_calories.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
+
+#[cfg(test)]
+mod test_top_n {
+    use super::*;
+
+    #[test]
+    fn top_three() {
+        let elf_calories = vec![6000, 4000, 11000, 24000, 10000];
+        assert_eq!(top_n_calories(&elf_calories, 3), vec![24000, 11000, 10000]);
+        assert_eq!(top_n_heap(elf_calories, 3), vec![24000, 11000, 10000]);
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_elf() {
+        let elf_calories = vec![300, 100, 200, 100, 200];
+        let expected = vec![
+            Elf {
+                index: 0,
+                calories: 300,
+            },
+            Elf {
+                index: 2,
+                calories: 200,
+            },
+        ];
+        assert_eq!(top_n_elves(&elf_calories, 2), expected);
+        assert_eq!(top_n_elves_heap(elf_calories.clone(), 2), expected);
+        assert_eq!(top_n_elves(&elf_calories, 4)[3].index, 1);
+        assert_eq!(top_n_elves_heap(elf_calories, 4)[3].index, 1);
+    }
+
+    #[test]
+    fn rank_every_elf() {
+        let calories_notes = "100\n\n300\n\n100\n\n50\n50\n\n300";
+        let ranking = rank_elves(calories_notes)
+            .into_iter()
+            .map(|elf| (elf.index, elf.calories))
+            .collect::<Vec<(usize, i32)>>();
+        assert_eq!(
+            ranking,
+            vec![(1, 300), (4, 300), (0, 100), (2, 100), (3, 100)]
+        );
+    }
+
+    #[test]
+    fn heap_matches_sorting_on_random_data() {
+        // Small LCG so the test stays deterministic without pulling in a crate.
+        let mut seed: u64 = 42;
+        let elf_calories = (0..1000)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((seed >> 33) % 100_000) as i32
+            })
+            .collect::<Vec<i32>>();
+
+        for n in [0, 1, 3, 10, 999, 1000, 1500] {
+            assert_eq!(
+                top_n_heap(elf_calories.iter().copied(), n),
+                top_n_calories(&elf_calories, n)
+            );
+            assert_eq!(
+                top_n_elves_heap(elf_calories.iter().copied(), n),
+                top_n_elves(&elf_calories, n)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_top_elf_excess {
+    use super::*;
+
+    #[test]
+    fn known_elves() {
+        let elf_calories = vec![6000, 4000, 11000, 24000, 10000];
+        assert_eq!(top_elf_excess(&elf_calories), Some(13000.0));
+        assert_eq!(top_elf_excess(&[500]), Some(0.0));
+    }
+
+    #[test]
+    fn no_elves() {
+        assert_eq!(top_elf_excess(&[]), None);
+    }
+}
+
+#[cfg(test)]
+mod test_max_in_windows {
+    use super::*;
+
+    #[test]
+    fn window_of_three() {
+        let elf_calories = vec![6000, 4000, 11000, 24000, 10000, 3000];
+        assert_eq!(
+            max_in_windows(&elf_calories, 3),
+            vec![11000, 24000, 24000, 24000]
+        );
+    }
+
+    #[test]
+    fn window_wider_than_elves() {
+        assert_eq!(max_in_windows(&[6000, 4000], 3), Vec::<i32>::new());
+        assert_eq!(max_in_windows(&[6000, 4000], 0), Vec::<i32>::new());
+    }
+}
+
+#[cfg(test)]
+mod test_report {
+    use super::*;
+
+    #[test]
+    fn known_notes() {
+        let calories_notes = "1000
+    2000
+    3000
+
+    4000
+
+    5000
+    6000
+
+    7000
+    8000
+    9000
+
+    10000";
+        let calorie_report = report(calories_notes).unwrap();
+        assert_eq!(
+            calorie_report,
+            CalorieReport {
+                elves: 5,
+                top_elf: Some(Elf {
+                    index: 3,
+                    calories: 24000
+                }),
+                top_three_total: 45000,
+                mean: 11000.0,
+            }
+        );
+        assert_eq!(
+            calorie_report.to_string(),
+            "Elves: 5\nTop elf: #4 with 24000\nTop 3 total: 45000\nMean: 11000.00"
+        );
+    }
+
+    #[test]
+    fn no_elves() {
+        let calorie_report = report("").unwrap();
+        assert_eq!(calorie_report.top_elf, None);
+        assert_eq!(
+            calorie_report.to_string(),
+            "Elves: 0\nTop elf: none\nTop 3 total: 0\nMean: 0.00"
+        );
+    }
+
+    #[test]
+    fn invalid_notes() {
+        assert_eq!(
+            report("100\n\nabc"),
+            Err(ParseError {
+                line: 3,
+                raw: "abc".to_string()
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_format_ranking {
+    use super::*;
+
+    #[test]
+    fn three_elves() {
+        assert_eq!(
+            format_ranking(&[24000, 11000, 10000]),
+            "1) 24000\n2) 11000\n3) 10000"
+        );
+    }
+
+    #[test]
+    fn no_elves() {
+        assert_eq!(format_ranking(&[]), "");
+    }
 }
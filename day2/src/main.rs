@@ -1,11 +1,12 @@
+use std::collections::HashSet;
 use std::fs::read_to_string;
 fn main() {
     let input_filepath = "./input/strategy_guide.txt";
     // reads the input string into the game
     let rounds_raw = read_to_string(input_filepath)
-        .expect(format!("Unable to read {}", input_filepath).as_str());
+        .unwrap_or_else(|_| panic!("Unable to read {}", input_filepath));
 
-    for part in vec![Problem::Part1, Problem::Part2] {
+    for part in [Problem::Part1, Problem::Part2] {
         let game = RockPaperScissors::new(rounds_raw.as_str(), part);
         let outcome = game.run();
 
@@ -63,7 +64,13 @@ enum Outcome {
     Draw,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+enum ParseGuideError {
+    InvalidOpponentWord(String),
+    InvalidMyselfWord(String),
+}
+
+#[derive(Debug, PartialEq)]
 struct RoundSetup {
     opponent: Hand,
     myself: Hand,
@@ -76,6 +83,13 @@ struct RoundOutcome {
     score_myself: i32,
 }
 
+// My total score, split into what the hands I played and the round outcomes earned me.
+#[derive(Debug, PartialEq)]
+struct ScoreBreakdown {
+    hand_points: i32,
+    outcome_points: i32,
+}
+
 struct RockPaperScissors {
     rounds_setup: Vec<RoundSetup>,
 }
@@ -125,79 +139,536 @@ impl Game for RockPaperScissors {
     }
 
     fn run(&self) -> RoundOutcome {
-        let mut game_outcome = RoundOutcome {
-            winner: Outcome::Draw,
-            score_opponent: 0,
-            score_myself: 0,
-        };
-        for round in &self.rounds_setup {
-            let round_outcome = round.play();
-            game_outcome.score_opponent += round_outcome.score_opponent;
-            game_outcome.score_myself += round_outcome.score_myself;
+        aggregate_from_rounds(&self.run_detailed())
+    }
+}
+
+// Sums up the scores of each round, and tells the overall winner from the totals.
+fn aggregate_from_rounds(rounds: &[RoundOutcome]) -> RoundOutcome {
+    let mut game_outcome = RoundOutcome {
+        winner: Outcome::Draw,
+        score_opponent: 0,
+        score_myself: 0,
+    };
+    for round_outcome in rounds {
+        game_outcome.score_opponent += round_outcome.score_opponent;
+        game_outcome.score_myself += round_outcome.score_myself;
+    }
+
+    game_outcome.winner = if game_outcome.score_myself == game_outcome.score_opponent {
+        Outcome::Draw
+    } else if game_outcome.score_myself > game_outcome.score_opponent {
+        Outcome::MyselfWins
+    } else {
+        Outcome::OpponentWins
+    };
+    game_outcome
+}
+
+impl RockPaperScissors {
+    // The outcome of every round, in order.
+    fn run_detailed(&self) -> Vec<RoundOutcome> {
+        self.rounds_setup.iter().map(|round| round.play()).collect()
+    }
+
+    // Same as `run`, but the opponent sees my hand coming and always counters it.
+    #[allow(dead_code)]
+    fn run_vs_adaptive_opponent(&self) -> RoundOutcome {
+        let rounds = self
+            .rounds_setup
+            .iter()
+            .map(|round| {
+                RoundSetup {
+                    opponent: round.myself.beaten_by(),
+                    myself: round.myself,
+                }
+                .play()
+            })
+            .collect::<Vec<RoundOutcome>>();
+        aggregate_from_rounds(&rounds)
+    }
+
+    // (myself, opponent) totals where round `i` is scaled by `weights[i]`.
+    // Rounds past the end of `weights` keep a weight of 1.0.
+    #[allow(dead_code)]
+    fn run_weighted(&self, weights: &[f64]) -> (f64, f64) {
+        self.run_detailed()
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(myself, opponent), (i, round)| {
+                let weight = weights.get(i).copied().unwrap_or(1.0);
+                (
+                    myself + weight * round.score_myself as f64,
+                    opponent + weight * round.score_opponent as f64,
+                )
+            })
+    }
+
+    // Same as `run`, but hands are worth nothing: only wins and draws score.
+    #[allow(dead_code)]
+    fn run_outcome_only(&self) -> RoundOutcome {
+        let rounds = self
+            .run_detailed()
+            .into_iter()
+            .map(|round| RoundOutcome {
+                score_opponent: round.winner.reversed().score(),
+                score_myself: round.winner.score(),
+                winner: round.winner,
+            })
+            .collect::<Vec<RoundOutcome>>();
+        aggregate_from_rounds(&rounds)
+    }
+
+    // The ceiling of my performance: disregard the guide's myself column and win every round.
+    #[allow(dead_code)]
+    fn optimal_myself_score(&self) -> i32 {
+        self.rounds_setup.iter().fold(0, |acc, round| {
+            let optimal_round = RoundSetup {
+                opponent: round.opponent,
+                myself: round.opponent.beaten_by(),
+            };
+            acc + optimal_round.play().score_myself
+        })
+    }
+
+    // How many points the guide's myself column leaves on the table compared to winning every round.
+    #[allow(dead_code)]
+    fn regret(&self) -> i32 {
+        self.optimal_myself_score() - self.run().score_myself
+    }
+
+    // Same as `regret`, from the opponent's side: what they'd score by winning every round,
+    // minus what they actually scored.
+    #[allow(dead_code)]
+    fn opponent_regret(&self) -> i32 {
+        let optimal_opponent_score = self.rounds_setup.iter().fold(0, |acc, round| {
+            let optimal_round = RoundSetup {
+                opponent: round.myself.beaten_by(),
+                myself: round.myself,
+            };
+            acc + optimal_round.play().score_opponent
+        });
+        optimal_opponent_score - self.run().score_opponent
+    }
+
+    // The floor of my performance: disregard the guide's myself column and lose every round.
+    #[allow(dead_code)]
+    fn worst_myself_score(&self) -> i32 {
+        self.rounds_setup.iter().fold(0, |acc, round| {
+            let worst_round = RoundSetup {
+                opponent: round.opponent,
+                myself: required_hand(round.opponent, Outcome::OpponentWins),
+            };
+            acc + worst_round.play().score_myself
+        })
+    }
+
+    // Shortest block of opponent hands that, repeated whole at least twice, makes up the guide.
+    // None when the opponent doesn't follow such a cycle.
+    #[allow(dead_code)]
+    fn detect_opponent_cycle(&self) -> Option<Vec<Hand>> {
+        let opponent = self
+            .rounds_setup
+            .iter()
+            .map(|round| round.opponent)
+            .collect::<Vec<Hand>>();
+        (1..=opponent.len() / 2)
+            .filter(|period| opponent.len() % period == 0)
+            .find(|&period| (period..opponent.len()).all(|i| opponent[i] == opponent[i - period]))
+            .map(|period| opponent[..period].to_vec())
+    }
+
+    // Running (myself, opponent) totals after each round.
+    #[allow(dead_code)]
+    fn cumulative_scores(&self) -> Vec<(i32, i32)> {
+        let mut totals = (0, 0);
+        self.rounds_setup
+            .iter()
+            .map(|round| {
+                let round_outcome = round.play();
+                totals.0 += round_outcome.score_myself;
+                totals.1 += round_outcome.score_opponent;
+                totals
+            })
+            .collect()
+    }
+
+    // (0-based) round after which my running total first goes above the opponent's.
+    #[allow(dead_code)]
+    fn first_lead_round(&self) -> Option<usize> {
+        self.cumulative_scores()
+            .iter()
+            .position(|(myself, opponent)| myself > opponent)
+    }
+
+    // How many rounds I (won, drew, lost).
+    #[allow(dead_code)]
+    fn outcome_tally(&self) -> (usize, usize, usize) {
+        self.rounds_setup
+            .iter()
+            .fold((0, 0, 0), |(won, drew, lost), round| {
+                match round.play().winner {
+                    Outcome::MyselfWins => (won + 1, drew, lost),
+                    Outcome::Draw => (won, drew + 1, lost),
+                    Outcome::OpponentWins => (won, drew, lost + 1),
+                }
+            })
+    }
+
+    #[allow(dead_code)]
+    fn score_breakdown(&self) -> ScoreBreakdown {
+        self.rounds_setup.iter().fold(
+            ScoreBreakdown {
+                hand_points: 0,
+                outcome_points: 0,
+            },
+            |breakdown, round| ScoreBreakdown {
+                hand_points: breakdown.hand_points + round.myself.score(),
+                outcome_points: breakdown.outcome_points + round.play().winner.score(),
+            },
+        )
+    }
+
+    // (0-based) indices of the rounds I lost.
+    #[allow(dead_code)]
+    fn lost_round_indices(&self) -> Vec<usize> {
+        self.run_detailed()
+            .iter()
+            .enumerate()
+            .filter(|(_, round)| round.winner == Outcome::OpponentWins)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Percentage of rounds I (won, drew, lost). All zeros when there are no rounds.
+    #[allow(dead_code)]
+    fn outcome_percentages(&self) -> (f64, f64, f64) {
+        let rounds = self.rounds_setup.len();
+        if rounds == 0 {
+            return (0.0, 0.0, 0.0);
         }
+        let (won, drew, lost) = self.outcome_tally();
+        let percentage = |count: usize| count as f64 * 100.0 / rounds as f64;
+        (percentage(won), percentage(drew), percentage(lost))
+    }
 
-        game_outcome.winner = if game_outcome.score_myself == game_outcome.score_opponent {
-            Outcome::Draw
-        } else if game_outcome.score_myself > game_outcome.score_opponent {
-            Outcome::MyselfWins
-        } else {
-            Outcome::OpponentWins
-        };
-        return game_outcome;
+    // Lexicographically first sequence of my hands (Rock < Paper < Scissors) that scores exactly `target`.
+    #[allow(dead_code)]
+    fn hands_for_exact_score(opponent: &[Hand], target: i32) -> Option<Vec<Hand>> {
+        let hands = [Hand::Rock, Hand::Paper, Hand::Scissors];
+        let score =
+            |opponent: Hand, myself: Hand| RoundSetup { opponent, myself }.play().score_myself;
+
+        // reachable[i] holds every total that rounds `i..` are able to score.
+        let mut reachable = vec![HashSet::from([0])];
+        for &opponent_hand in opponent.iter().rev() {
+            let next = reachable.last().expect("Missing reachable scores");
+            let current = hands
+                .iter()
+                .flat_map(|&hand| {
+                    next.iter()
+                        .map(move |total| total + score(opponent_hand, hand))
+                })
+                .collect::<HashSet<i32>>();
+            reachable.push(current);
+        }
+        reachable.reverse();
+
+        if !reachable[0].contains(&target) {
+            return None;
+        }
+        // Walk forward picking the smallest hand that keeps the remaining target reachable.
+        let mut remaining = target;
+        let mut myself_hands = Vec::new();
+        for (i, &opponent_hand) in opponent.iter().enumerate() {
+            let hand = *hands
+                .iter()
+                .find(|&&hand| reachable[i + 1].contains(&(remaining - score(opponent_hand, hand))))
+                .expect("Target should remain reachable");
+            remaining -= score(opponent_hand, hand);
+            myself_hands.push(hand);
+        }
+        Some(myself_hands)
+    }
+
+    // Writes the rounds back as a guide, one `<opponent> <myself>` line per round. The myself
+    // column holds my hand for the first part, and the round's outcome for the second one.
+    #[allow(dead_code)]
+    fn to_guide_string(&self, problem: Problem) -> String {
+        self.rounds_setup
+            .iter()
+            .map(|round| {
+                let myself_word = match problem {
+                    Problem::Part1 => round.myself.to_myself_char(),
+                    Problem::Part2 => match round.play().winner {
+                        Outcome::OpponentWins => 'X',
+                        Outcome::Draw => 'Y',
+                        Outcome::MyselfWins => 'Z',
+                    },
+                };
+                format!("{} {}", round.opponent.to_opponent_char(), myself_word)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // The first (1-based) line of the guide that fails to parse, without looking any further.
+    #[allow(dead_code)]
+    fn first_invalid_round(rounds: &str, problem: Problem) -> Option<(usize, String)> {
+        rounds
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .find(|(_, line)| RoundSetup::try_new(line.trim(), problem).is_err())
+            .map(|(i, line)| (i + 1, line.trim().to_string()))
+    }
+
+    // Fewest rounds I'd need to switch to a winning hand to turn the game into a win.
+    // Rounds are independent, so greedily taking the largest swings first is optimal.
+    #[allow(dead_code)]
+    fn min_changes_to_win(&self) -> Option<usize> {
+        let outcome = self.run();
+        let mut margin = outcome.score_myself - outcome.score_opponent;
+        if margin > 0 {
+            return None;
+        }
+
+        let mut swings = self
+            .rounds_setup
+            .iter()
+            .map(|round| {
+                let current = round.play();
+                let winning = RoundSetup {
+                    opponent: round.opponent,
+                    myself: round.opponent.beaten_by(),
+                }
+                .play();
+                (winning.score_myself - winning.score_opponent)
+                    - (current.score_myself - current.score_opponent)
+            })
+            .filter(|swing| *swing > 0)
+            .collect::<Vec<i32>>();
+        swings.sort_by(|a, b| b.cmp(a));
+
+        for (changes, swing) in swings.iter().enumerate() {
+            margin += swing;
+            if margin > 0 {
+                return Some(changes + 1);
+            }
+        }
+        None
+    }
+}
+
+impl Outcome {
+    // Bonus I earn from the round: win, draw or loss.
+    fn score(&self) -> i32 {
+        match self {
+            Outcome::MyselfWins => 6,
+            Outcome::Draw => 3,
+            Outcome::OpponentWins => 0,
+        }
+    }
+
+    // The same outcome, as seen from the opponent's side.
+    fn reversed(&self) -> Outcome {
+        match self {
+            Outcome::MyselfWins => Outcome::OpponentWins,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::OpponentWins => Outcome::MyselfWins,
+        }
+    }
+}
+
+impl Hand {
+    fn score(&self) -> i32 {
+        *self as i32
+    }
+
+    // Opponent words: A, B and C.
+    fn from_opponent_char(word: char) -> Option<Hand> {
+        match word {
+            'A' => Some(Hand::Rock),
+            'B' => Some(Hand::Paper),
+            'C' => Some(Hand::Scissors),
+            _ => None,
+        }
+    }
+
+    // Myself words: X, Y and Z. Only meaningful as hands for the first part of the problem.
+    fn from_myself_char(word: char) -> Option<Hand> {
+        match word {
+            'X' => Some(Hand::Rock),
+            'Y' => Some(Hand::Paper),
+            'Z' => Some(Hand::Scissors),
+            _ => None,
+        }
+    }
+
+    fn to_opponent_char(self) -> char {
+        match self {
+            Hand::Rock => 'A',
+            Hand::Paper => 'B',
+            Hand::Scissors => 'C',
+        }
+    }
+
+    fn to_myself_char(self) -> char {
+        match self {
+            Hand::Rock => 'X',
+            Hand::Paper => 'Y',
+            Hand::Scissors => 'Z',
+        }
+    }
+
+    fn beaten_by(&self) -> Hand {
+        match self {
+            Hand::Rock => Hand::Paper,
+            Hand::Paper => Hand::Scissors,
+            Hand::Scissors => Hand::Rock,
+        }
+    }
+}
+
+// The hand I have to play against `opponent` so that the round ends up as `desired`.
+fn required_hand(opponent: Hand, desired: Outcome) -> Hand {
+    match desired {
+        Outcome::MyselfWins => opponent.beaten_by(),
+        Outcome::Draw => opponent,
+        // Whatever beats the hand that beats the opponent loses to it.
+        Outcome::OpponentWins => opponent.beaten_by().beaten_by(),
+    }
+}
+
+#[cfg(test)]
+mod test_hand {
+    use super::*;
+
+    #[test]
+    fn test_from_opponent_char() {
+        assert_eq!(Hand::from_opponent_char('A'), Some(Hand::Rock));
+        assert_eq!(Hand::from_opponent_char('B'), Some(Hand::Paper));
+        assert_eq!(Hand::from_opponent_char('C'), Some(Hand::Scissors));
+        assert_eq!(Hand::from_opponent_char('X'), None);
+    }
+
+    #[test]
+    fn test_to_char() {
+        for hand in [Hand::Rock, Hand::Paper, Hand::Scissors] {
+            assert_eq!(
+                Hand::from_opponent_char(hand.to_opponent_char()),
+                Some(hand)
+            );
+            assert_eq!(Hand::from_myself_char(hand.to_myself_char()), Some(hand));
+        }
+    }
+
+    #[test]
+    fn test_from_myself_char() {
+        assert_eq!(Hand::from_myself_char('X'), Some(Hand::Rock));
+        assert_eq!(Hand::from_myself_char('Y'), Some(Hand::Paper));
+        assert_eq!(Hand::from_myself_char('Z'), Some(Hand::Scissors));
+        assert_eq!(Hand::from_myself_char('A'), None);
+    }
+
+    #[test]
+    fn test_beaten_by() {
+        for hand in [Hand::Rock, Hand::Paper, Hand::Scissors] {
+            assert_eq!(
+                hand.beaten_by().partial_cmp(&hand),
+                Some(std::cmp::Ordering::Greater)
+            );
+        }
+    }
+
+    #[test]
+    fn test_hand_score() {
+        assert_eq!(Hand::Rock.score(), 1);
+        assert_eq!(Hand::Paper.score(), 2);
+        assert_eq!(Hand::Scissors.score(), 3);
+    }
+
+    #[test]
+    fn test_outcome_score() {
+        assert_eq!(Outcome::MyselfWins.score(), 6);
+        assert_eq!(Outcome::Draw.score(), 3);
+        assert_eq!(Outcome::OpponentWins.score(), 0);
+        assert_eq!(Outcome::MyselfWins.reversed().score(), 0);
+        assert_eq!(Outcome::Draw.reversed().score(), 3);
+        assert_eq!(Outcome::OpponentWins.reversed().score(), 6);
+    }
+
+    #[test]
+    fn test_required_hand() {
+        let table = [
+            (Hand::Rock, Outcome::OpponentWins, Hand::Scissors),
+            (Hand::Paper, Outcome::OpponentWins, Hand::Rock),
+            (Hand::Scissors, Outcome::OpponentWins, Hand::Paper),
+            (Hand::Rock, Outcome::Draw, Hand::Rock),
+            (Hand::Paper, Outcome::Draw, Hand::Paper),
+            (Hand::Scissors, Outcome::Draw, Hand::Scissors),
+            (Hand::Rock, Outcome::MyselfWins, Hand::Paper),
+            (Hand::Paper, Outcome::MyselfWins, Hand::Scissors),
+            (Hand::Scissors, Outcome::MyselfWins, Hand::Rock),
+        ];
+        for (opponent, desired, expected) in table {
+            assert_eq!(required_hand(opponent, desired), expected);
+        }
     }
 }
 
 impl RoundSetup {
     fn new(line: &str, problem: Problem) -> Self {
+        RoundSetup::try_new(line, problem)
+            .unwrap_or_else(|err| panic!("Invalid round {:?}: {}", err, line))
+    }
+
+    fn try_new(line: &str, problem: Problem) -> Result<Self, ParseGuideError> {
+        let (opponent, myself_word) = RoundSetup::parse_words(line)?;
+        RoundSetup::from_words(opponent, myself_word, problem)
+    }
+
+    // Splits a guide line into the opponent's hand and the (yet to be interpreted) myself word.
+    fn parse_words(line: &str) -> Result<(Hand, &str), ParseGuideError> {
         let mut words = line.split_whitespace();
-        let opponent = match words.next() {
-            Some("A") => Hand::Rock,
-            Some("B") => Hand::Paper,
-            Some("C") => Hand::Scissors,
-            _ => panic!("Invalid opponent hand"),
-        };
+        let opponent_word = words.next().unwrap_or_default();
+        let opponent = opponent_word
+            .parse::<char>()
+            .ok()
+            .and_then(Hand::from_opponent_char)
+            .ok_or_else(|| ParseGuideError::InvalidOpponentWord(opponent_word.to_string()))?;
+        Ok((opponent, words.next().unwrap_or_default()))
+    }
+
+    fn from_words(
+        opponent: Hand,
+        myself_word: &str,
+        problem: Problem,
+    ) -> Result<Self, ParseGuideError> {
+        let invalid_myself_word = || ParseGuideError::InvalidMyselfWord(myself_word.to_string());
 
         // The way that we handle the second column i.e. `myself` differs
         // depending on which part of the problem we're at.
         let myself = match problem {
             // First part: we inferred that X, Y and Z were mappings to hands.
-            Problem::Part1 => match words.next() {
-                Some("X") => Hand::Rock,
-                Some("Y") => Hand::Paper,
-                Some("Z") => Hand::Scissors,
-                _ => panic!("Invalid myself hand"),
-            },
+            Problem::Part1 => myself_word
+                .parse::<char>()
+                .ok()
+                .and_then(Hand::from_myself_char)
+                .ok_or_else(invalid_myself_word)?,
             // Second part: we know that X, Y and Z are mappings to outcomes that
             // depends on the opponent's hand.
-            Problem::Part2 => match words.next() {
-                    Some("X") /* I need to lose */ => { 
-                        match opponent {
-                            Hand::Rock => Hand::Scissors,
-                            Hand::Paper => Hand::Rock,
-                            Hand::Scissors => Hand::Paper,
-                        }
-                    },
-                    Some("Y") /* I need to draw */ => {
-                        match opponent {
-                            Hand::Rock => Hand::Rock,
-                            Hand::Paper => Hand::Paper,
-                            Hand::Scissors => Hand::Scissors,
-                        }
-                    },
-                    Some("Z") /* I need to win */ => {
-                        match opponent {
-                            Hand::Rock => Hand::Paper,
-                            Hand::Paper => Hand::Scissors,
-                            Hand::Scissors => Hand::Rock,
-                        }
-                    },
-                    _ => panic!("Invalid word.")
-            },
+            Problem::Part2 => {
+                let desired = match myself_word {
+                    "X" /* I need to lose */ => Outcome::OpponentWins,
+                    "Y" /* I need to draw */ => Outcome::Draw,
+                    "Z" /* I need to win */ => Outcome::MyselfWins,
+                    _ => return Err(invalid_myself_word()),
+                };
+                required_hand(opponent, desired)
+            }
         };
-        Self {
-            opponent: opponent,
-            myself: myself,
-        }
+        Ok(Self { opponent, myself })
     }
 
     fn play(&self) -> RoundOutcome {
@@ -208,23 +679,95 @@ impl RoundSetup {
             None => panic!("Invalid round"),
         };
 
-        let score_opponent = match winner {
-            Outcome::OpponentWins => self.opponent as i32 + 6,
-            Outcome::Draw => self.opponent as i32 + 3,
-            Outcome::MyselfWins => self.opponent as i32,
-        };
-
-        let score_myself = match winner {
-            Outcome::MyselfWins => self.myself as i32 + 6,
-            Outcome::Draw => self.myself as i32 + 3,
-            Outcome::OpponentWins => self.myself as i32,
-        };
+        let score_opponent = self.opponent.score() + winner.reversed().score();
+        let score_myself = self.myself.score() + winner.score();
 
-        return RoundOutcome {
+        RoundOutcome {
             winner,
             score_opponent,
             score_myself,
-        };
+        }
+    }
+}
+
+// Parses and plays a single line of the guide.
+#[allow(dead_code)]
+fn score_round(line: &str, problem: Problem) -> Result<RoundOutcome, ParseGuideError> {
+    Ok(RoundSetup::try_new(line.trim(), problem)?.play())
+}
+
+#[cfg(test)]
+mod test_score_round {
+    use super::*;
+
+    #[test]
+    fn part1() {
+        let outcome = score_round("A Y", Problem::Part1).unwrap();
+        assert_eq!(outcome.winner, Outcome::MyselfWins);
+        assert_eq!(outcome.score_myself, 8);
+        assert_eq!(outcome.score_opponent, 1);
+    }
+
+    #[test]
+    fn part2() {
+        let outcome = score_round("A Y", Problem::Part2).unwrap();
+        assert_eq!(outcome.winner, Outcome::Draw);
+        assert_eq!(outcome.score_myself, 4);
+        assert_eq!(outcome.score_opponent, 4);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            score_round("A", Problem::Part1).unwrap_err(),
+            ParseGuideError::InvalidMyselfWord("".to_string())
+        );
+    }
+}
+
+// Parses each line of the guide once, and plays it under both interpretations.
+// Returns the (Part 1, Part 2) totals for myself.
+#[allow(dead_code)]
+fn solve_both_parts(rounds: &str) -> Result<(i32, i32), ParseGuideError> {
+    let mut totals = (0, 0);
+    for line in rounds.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (opponent, myself_word) = RoundSetup::parse_words(line.trim())?;
+        totals.0 += RoundSetup::from_words(opponent, myself_word, Problem::Part1)?
+            .play()
+            .score_myself;
+        totals.1 += RoundSetup::from_words(opponent, myself_word, Problem::Part2)?
+            .play()
+            .score_myself;
+    }
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod test_solve_both_parts {
+    use super::*;
+
+    #[test]
+    fn example_guide() {
+        let rounds = "A Y
+        B X
+        C Z";
+
+        assert_eq!(solve_both_parts(rounds), Ok((15, 12)));
+    }
+
+    #[test]
+    fn invalid_guide() {
+        assert_eq!(
+            solve_both_parts("A Y\nD X"),
+            Err(ParseGuideError::InvalidOpponentWord("D".to_string()))
+        );
+        assert_eq!(
+            solve_both_parts("A Y\nB W"),
+            Err(ParseGuideError::InvalidMyselfWord("W".to_string()))
+        );
     }
 }
 
@@ -310,6 +853,331 @@ mod test_game {
         assert_eq!(outcome.score_myself, 1 + 5 + 6);
         assert_eq!(outcome.score_opponent, 8 + 5 + 6);
     }
+
+    #[test]
+    fn optimal_myself_score() {
+        // Arrange
+        let rounds = "A X
+        B X
+        C X
+        A Z";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+
+        // Act
+        let score = game.optimal_myself_score();
+
+        // Assert
+        assert_eq!(score, 8 + 9 + 7 + 8);
+    }
+
+    #[test]
+    fn run_vs_adaptive_opponent() {
+        // Arrange
+        let game = RockPaperScissors::new("A Y\nB X\nC Z", Problem::Part1);
+
+        // Act
+        let outcome = game.run_vs_adaptive_opponent();
+
+        // Assert
+        // I lose every round, so I only ever get my hand's value.
+        assert_eq!(outcome.winner, Outcome::OpponentWins);
+        assert_eq!(outcome.score_myself, 2 + 1 + 3);
+        assert_eq!(outcome.score_opponent, (3 + 6) + (2 + 6) + (1 + 6));
+    }
+
+    #[test]
+    fn run_weighted() {
+        // Arrange
+        // Round scores (myself, opponent): (8, 1), (1, 8) and (6, 6).
+        let game = RockPaperScissors::new("A Y\nB X\nC Z", Problem::Part1);
+
+        // Act
+        let weighted = game.run_weighted(&[3.0, 0.5]);
+
+        // Assert
+        assert_eq!(weighted, (24.0 + 0.5 + 6.0, 3.0 + 4.0 + 6.0));
+        assert_eq!(game.run_weighted(&[]), (15.0, 15.0));
+    }
+
+    #[test]
+    fn run_outcome_only() {
+        // Arrange
+        let rounds = "A Y
+        B X
+        C Z
+        A Y";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+        let (won, drew, lost) = game.outcome_tally();
+
+        // Act
+        let outcome = game.run_outcome_only();
+
+        // Assert
+        assert_eq!(outcome.score_myself, 6 * won as i32 + 3 * drew as i32);
+        assert_eq!(outcome.score_opponent, 6 * lost as i32 + 3 * drew as i32);
+        assert_eq!(outcome.score_myself, 15);
+        assert_eq!(outcome.score_opponent, 9);
+        assert_eq!(outcome.winner, Outcome::MyselfWins);
+    }
+
+    #[test]
+    fn regret() {
+        // Arrange
+        let suboptimal = RockPaperScissors::new("A Y\nB X\nC Z", Problem::Part1);
+        let optimal = RockPaperScissors::new("A Y\nB Z\nC X", Problem::Part1);
+
+        // Act & assert
+        assert_eq!(suboptimal.regret(), 24 - 15);
+        assert_eq!(optimal.regret(), 0);
+    }
+
+    #[test]
+    fn opponent_regret() {
+        // Arrange
+        let game = RockPaperScissors::new("A Y\nB X\nC Z", Problem::Part1);
+        let optimal = RockPaperScissors::new("B X\nC Y\nA Z", Problem::Part1);
+
+        // Act & assert
+        assert_eq!(game.opponent_regret(), (9 + 8 + 7) - (1 + 8 + 6));
+        assert_eq!(optimal.opponent_regret(), 0);
+    }
+
+    #[test]
+    fn worst_myself_score() {
+        // Arrange
+        let rounds = "A X
+        B X
+        C X
+        A Z";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+
+        // Act
+        let score = game.worst_myself_score();
+
+        // Assert
+        assert_eq!(score, 3 + 1 + 2 + 3);
+    }
+
+    #[test]
+    fn min_changes_to_win() {
+        // Arrange
+        let losing = RockPaperScissors::new(
+            "B X
+        B X
+        B X",
+            Problem::Part1,
+        );
+        let winning = RockPaperScissors::new("B Z", Problem::Part1);
+
+        // Act & assert
+        // Each switch to scissors swings the margin by 14 points: -21 -> -7 -> 7.
+        assert_eq!(losing.min_changes_to_win(), Some(2));
+        assert_eq!(winning.min_changes_to_win(), None);
+    }
+
+    #[test]
+    fn aggregate_from_rounds() {
+        // Arrange
+        let rounds = vec![
+            RoundOutcome {
+                winner: Outcome::MyselfWins,
+                score_opponent: 1,
+                score_myself: 8,
+            },
+            RoundOutcome {
+                winner: Outcome::OpponentWins,
+                score_opponent: 9,
+                score_myself: 2,
+            },
+            RoundOutcome {
+                winner: Outcome::OpponentWins,
+                score_opponent: 7,
+                score_myself: 3,
+            },
+        ];
+
+        // Act
+        let outcome = super::aggregate_from_rounds(&rounds);
+        let empty_outcome = super::aggregate_from_rounds(&[]);
+
+        // Assert
+        assert_eq!(outcome.winner, Outcome::OpponentWins);
+        assert_eq!(outcome.score_myself, 8 + 2 + 3);
+        assert_eq!(outcome.score_opponent, 1 + 9 + 7);
+        assert_eq!(empty_outcome.winner, Outcome::Draw);
+        assert_eq!(empty_outcome.score_myself, 0);
+    }
+
+    #[test]
+    fn outcome_percentages() {
+        // Arrange
+        let rounds = "A Y
+        B X
+        C Z
+        A Z";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+        let empty_game = RockPaperScissors::new("", Problem::Part1);
+
+        // Act
+        let tally = game.outcome_tally();
+        let percentages = game.outcome_percentages();
+
+        // Assert
+        assert_eq!(tally, (1, 1, 2));
+        assert_eq!(percentages, (25.0, 25.0, 50.0));
+        assert_eq!(empty_game.outcome_percentages(), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn hands_for_exact_score() {
+        // Arrange
+        // Against rock: rock scores 4, paper 8 and scissors 3.
+        let opponent = [Hand::Rock, Hand::Rock];
+
+        // Act & assert
+        assert_eq!(
+            RockPaperScissors::hands_for_exact_score(&opponent, 7),
+            Some(vec![Hand::Rock, Hand::Scissors])
+        );
+        assert_eq!(
+            RockPaperScissors::hands_for_exact_score(&opponent, 6),
+            Some(vec![Hand::Scissors, Hand::Scissors])
+        );
+        assert_eq!(RockPaperScissors::hands_for_exact_score(&opponent, 9), None);
+        assert_eq!(RockPaperScissors::hands_for_exact_score(&opponent, 5), None);
+        assert_eq!(
+            RockPaperScissors::hands_for_exact_score(&[], 0),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn to_guide_string() {
+        // Arrange
+        let rounds = "A Y
+        B X
+        C Z";
+
+        for problem in [Problem::Part1, Problem::Part2] {
+            // Act
+            let game = RockPaperScissors::new(rounds, problem);
+            let guide = game.to_guide_string(problem);
+            let reparsed = RockPaperScissors::new(guide.as_str(), problem);
+
+            // Assert
+            assert_eq!(guide, "A Y\nB X\nC Z");
+            assert_eq!(reparsed.rounds_setup, game.rounds_setup);
+        }
+    }
+
+    #[test]
+    fn first_invalid_round() {
+        // Arrange
+        let rounds = "A Y
+        B W
+        D X";
+
+        // Act & assert
+        assert_eq!(
+            RockPaperScissors::first_invalid_round(rounds, Problem::Part1),
+            Some((2, "B W".to_string()))
+        );
+        assert_eq!(
+            RockPaperScissors::first_invalid_round("A Y\n\nC Z", Problem::Part2),
+            None
+        );
+    }
+
+    #[test]
+    fn detect_opponent_cycle() {
+        // Arrange
+        let cyclic = RockPaperScissors::new("A X\nB Y\nC Z\nA Y\nB Z\nC X", Problem::Part1);
+        let repeated = RockPaperScissors::new("B X\nB Y\nB Z", Problem::Part1);
+        let acyclic = RockPaperScissors::new("A X\nB Y\nC Z\nA Y\nB Z", Problem::Part1);
+
+        // Act & assert
+        assert_eq!(
+            cyclic.detect_opponent_cycle(),
+            Some(vec![Hand::Rock, Hand::Paper, Hand::Scissors])
+        );
+        assert_eq!(repeated.detect_opponent_cycle(), Some(vec![Hand::Paper]));
+        assert_eq!(acyclic.detect_opponent_cycle(), None);
+    }
+
+    #[test]
+    fn score_breakdown() {
+        // Arrange
+        let rounds = "A Y
+        B X
+        C Z";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+
+        // Act
+        let breakdown = game.score_breakdown();
+
+        // Assert
+        assert_eq!(
+            breakdown,
+            ScoreBreakdown {
+                hand_points: 2 + 1 + 3,
+                outcome_points: 9, // win, loss, draw
+            }
+        );
+        assert_eq!(
+            breakdown.hand_points + breakdown.outcome_points,
+            game.run().score_myself
+        );
+    }
+
+    #[test]
+    fn lost_round_indices() {
+        // Arrange
+        let rounds = "A Y
+        B X
+        C Z
+        A Z
+        C Y";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+
+        // Act
+        let lost = game.lost_round_indices();
+
+        // Assert
+        assert_eq!(lost, vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn first_lead_round() {
+        // Arrange
+        // Running totals (myself, opponent): (1, 8), (9, 9) and then (17, 10).
+        let game = RockPaperScissors::new("B X\nA Y\nA Y", Problem::Part1);
+        let trailing = RockPaperScissors::new("B X\nC Z", Problem::Part1);
+
+        // Act & assert
+        assert_eq!(game.first_lead_round(), Some(2));
+        assert_eq!(trailing.first_lead_round(), None);
+    }
+
+    #[test]
+    fn cumulative_scores() {
+        // Arrange
+        let rounds = "A Y
+        B X
+        C Z";
+        let game = RockPaperScissors::new(rounds, Problem::Part1);
+
+        // Act
+        let scores = game.cumulative_scores();
+        let outcome = game.run();
+
+        // Assert
+        assert_eq!(scores, vec![(8, 1), (9, 9), (15, 15)]);
+        assert_eq!(scores.len(), game.rounds_setup.len());
+        assert_eq!(
+            scores.last(),
+            Some(&(outcome.score_myself, outcome.score_opponent))
+        );
+    }
 }
 
 #[cfg(test)]
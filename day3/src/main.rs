@@ -1,18 +1,21 @@
 use std::collections::HashSet;
 use std::fs::read_to_string;
+use std::io::BufRead;
 
 fn main() {
     let input_path = "./input/rucksacks.txt";
     let input_contents =
-        read_to_string(input_path).expect(format!("Unable to read {}", input_path).as_str());
-    let game1 = GamePart1::new(input_contents.as_str());
+        read_to_string(input_path).unwrap_or_else(|_| panic!("Unable to read {}", input_path));
+    let game1 = GamePart1::new(input_contents.as_str())
+        .unwrap_or_else(|errors| panic!("Invalid rucksacks: {:?}", errors));
     let total_repetitive_items_priorities = game1.calculate_repetitive_item_priorities();
     println!(
         "Total repetitive items priorities: {}",
         total_repetitive_items_priorities
     );
 
-    let game2 = GamePart2::new(game1.rucksacks);
+    let game2 = GamePart2::new(game1.rucksacks, GamePart2::DEFAULT_GROUP_SIZE)
+        .unwrap_or_else(|err| panic!("Unable to group rucksacks: {:?}", err));
     let total_badges_priorities = game2.calculate_group_badges_priorities();
     println!("Total badges priorities: {}", total_badges_priorities);
 }
@@ -33,73 +36,201 @@ fn main() {
 // The Elf that did the packing failed to follow this rule for exactly one item type per rucksack.
 // There can be an arbitrary number of items of this type in either compartment.
 
+#[derive(Debug, PartialEq)]
+enum RucksackError {
+    Empty,
+    OddLength,
+    // Whitespace between items would otherwise be picked up as an item type.
+    InteriorWhitespace,
+    NoCommonItem,
+    MultipleCommonItems,
+    // The line itself couldn't be read, e.g. it isn't valid UTF-8.
+    Io(std::io::ErrorKind),
+    // The repetitive item has no priority, e.g. it's a digit.
+    UnknownItem(char),
+}
+
+// A rucksack that couldn't be read, along with the (1-based) line it came from.
+#[derive(Debug, PartialEq)]
+struct RucksackLineError {
+    line: usize,
+    kind: RucksackError,
+}
+
 #[derive(Clone, Debug)]
 struct Rucksack {
     items: Vec<char>,
     compartments: (HashSet<char>, HashSet<char>),
-    repetitive_item: char,
+    // Items before this index make up the first compartment, the rest the second one.
+    compartment_split: usize,
+    // None for anomalous rucksacks, i.e. when compartments don't share exactly one item type.
+    repetitive_item: Option<char>,
 }
 
 impl Rucksack {
-    fn new(line: &str) -> Self {
-        // let mut compartments = (Vec::new(), Vec::new());
+    fn new(line: &str) -> Result<Self, RucksackError> {
+        let rucksack = Rucksack::new_lenient(line)?;
+
+        // Invariant: One repetitive item type.
+        match rucksack.common_items_count() {
+            0 => Err(RucksackError::NoCommonItem),
+            1 => Ok(rucksack),
+            _ => Err(RucksackError::MultipleCommonItems),
+        }
+    }
+
+    // Same as `new`, but lets rucksacks that break the repetitive item invariant through.
+    fn new_lenient(line: &str) -> Result<Self, RucksackError> {
         let sanitized_line = line.trim();
         if sanitized_line.is_empty() {
-            panic!("Invalid odd line: {}", line);
+            return Err(RucksackError::Empty);
         }
 
-        // Invariant: Only even lines as the size of compartments should be the same.
-        if sanitized_line.len() % 2 != 0 {
-            panic!("Invalid odd line: {}", line);
+        // Invariant: Leading and trailing indentation is fine, but items are never separated by whitespace.
+        if sanitized_line.chars().any(char::is_whitespace) {
+            return Err(RucksackError::InteriorWhitespace);
         }
 
-        let (first_compartment, second_compartment) =
-            sanitized_line.split_at(sanitized_line.len() / 2);
-        let first_compartment_set: HashSet<char> = first_compartment.chars().collect();
-        let second_compartment_set: HashSet<char> = second_compartment.chars().collect();
-        let mut repetitive_items = first_compartment_set.intersection(&second_compartment_set);
-
-        // Invariant: One repetitive item type.
-        if repetitive_items.clone().count() != 1 {
-            panic!("Invalid repetitive items: {}", line);
+        // Invariant: Only even lines as the size of compartments should be the same.
+        let items: Vec<char> = sanitized_line.chars().collect();
+        if !items.len().is_multiple_of(2) {
+            return Err(RucksackError::OddLength);
         }
 
-        let repetitive_item = repetitive_items
-            .next()
-            .expect("Unexpected unwrapping of repetitive_items");
+        let (first_compartment, second_compartment) = items.split_at(items.len() / 2);
+        let first_compartment_set: HashSet<char> = first_compartment.iter().copied().collect();
+        let second_compartment_set: HashSet<char> = second_compartment.iter().copied().collect();
+        let repetitive_items = first_compartment_set
+            .intersection(&second_compartment_set)
+            .collect::<Vec<&char>>();
+        let repetitive_item = match repetitive_items[..] {
+            [repetitive_item] => Some(*repetitive_item),
+            _ => None,
+        };
 
-        Rucksack {
+        Ok(Rucksack {
             compartments: (
                 first_compartment_set.to_owned(),
                 second_compartment_set.to_owned(),
             ),
-            items: sanitized_line.chars().collect(),
-            repetitive_item: repetitive_item.clone(),
-        }
+            compartment_split: first_compartment.len(),
+            items,
+            repetitive_item,
+        })
     }
 
     fn get_items(&self) -> &Vec<char> {
         &self.items
     }
 
+    // Every occurrence of the repetitive item (in either compartment) counts towards the priority.
+    #[allow(dead_code)]
+    fn weighted_priority(&self) -> i32 {
+        let priority = Rucksack::get_item_priority(None, Some(self));
+        let occurrences = self
+            .items
+            .iter()
+            .filter(|item| Some(**item) == self.repetitive_item)
+            .count() as i32;
+        priority * occurrences
+    }
+
+    // Item counts (not item types) of the first and second compartments. Equal for any valid rucksack.
+    #[allow(dead_code)]
+    fn compartment_sizes(&self) -> (usize, usize) {
+        (
+            self.compartment_split,
+            self.items.len() - self.compartment_split,
+        )
+    }
+
+    // Highest priority among every item in the rucksack, regardless of compartment.
+    #[allow(dead_code)]
+    fn max_item_priority(&self) -> i32 {
+        self.items
+            .iter()
+            .map(|item| Rucksack::get_item_priority(Some(*item), None))
+            .max()
+            .expect("Rucksack without items")
+    }
+
+    fn common_items_count(&self) -> usize {
+        self.compartments
+            .0
+            .intersection(&self.compartments.1)
+            .count()
+    }
+
     fn get_item_priority(item_type: Option<char>, rucksack: Option<&Rucksack>) -> i32 {
         let item = match item_type {
             Some(item_type) => item_type,
             None => match rucksack {
-                Some(rucksack) => rucksack.repetitive_item,
+                Some(rucksack) => rucksack
+                    .repetitive_item
+                    .expect("Rucksack without a single repetitive item"),
                 None => panic!("Invalid param usage"),
             },
         };
 
+        DefaultPriorityScheme
+            .priority(item)
+            .unwrap_or_else(|| panic!("Unable to find item type {} in alphabet", item))
+    }
+}
+
+// Maps an item type to its priority. None for item types the scheme doesn't know about.
+trait PriorityScheme {
+    fn priority(&self, item: char) -> Option<i32>;
+}
+
+// Priority is the (1-based) position of the item in `a..zA..Z`.
+struct DefaultPriorityScheme;
+
+impl PriorityScheme for DefaultPriorityScheme {
+    fn priority(&self, item: char) -> Option<i32> {
         let alphabet = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let index = alphabet
-            .find(item)
-            .expect(format!("Unable to find item type {} in alphabet", item).as_str())
-            as i32;
-        return index + 1;
+        alphabet.find(item).map(|index| index as i32 + 1)
     }
 }
 
+#[allow(dead_code)]
+fn line_priority(line: &str) -> Result<i32, RucksackError> {
+    let rucksack = Rucksack::new(line)?;
+    let item = rucksack
+        .repetitive_item
+        .expect("Valid rucksacks always have a repetitive item");
+    DefaultPriorityScheme
+        .priority(item)
+        .ok_or(RucksackError::UnknownItem(item))
+}
+
+// Streams rucksacks line by line. A bad line is reported with its (1-based) number instead of
+// aborting the whole read. Blank lines are skipped.
+fn rucksacks_from_reader<R: BufRead>(reader: R) -> (Vec<Rucksack>, Vec<RucksackLineError>) {
+    let mut rucksacks = Vec::new();
+    let mut errors = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                errors.push(RucksackLineError {
+                    line: i + 1,
+                    kind: RucksackError::Io(err.kind()),
+                });
+                continue;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match Rucksack::new(line.as_str()) {
+            Ok(rucksack) => rucksacks.push(rucksack),
+            Err(kind) => errors.push(RucksackLineError { line: i + 1, kind }),
+        }
+    }
+    (rucksacks, errors)
+}
+
 #[cfg(test)]
 mod test_rucksack {
     use super::*;
@@ -107,30 +238,28 @@ mod test_rucksack {
     #[test]
     fn test_create_rucksack_basic() {
         // Arrange & act
-        let rucksack = Rucksack::new("ffabcCBADf");
+        let rucksack = Rucksack::new("ffabcCBADf").unwrap();
 
         // Assert
         assert_eq!(rucksack.compartments.0.len(), 4); // Note that we're comparing the size of sets, so if the repetitive item repeats, the sizes will be different.
         assert_eq!(rucksack.compartments.1.len(), 5);
         assert_eq!(
             rucksack.compartments.0,
-            vec!['f', 'a', 'b', 'c']
-                .into_iter()
-                .collect::<HashSet<char>>()
+            ['f', 'a', 'b', 'c'].into_iter().collect::<HashSet<char>>()
         );
         assert_eq!(
             rucksack.compartments.1,
-            vec!['C', 'B', 'A', 'D', 'f']
+            ['C', 'B', 'A', 'D', 'f']
                 .into_iter()
                 .collect::<HashSet<char>>()
         );
-        assert_eq!(rucksack.repetitive_item, 'f');
+        assert_eq!(rucksack.repetitive_item, Some('f'));
     }
 
     #[test]
     fn test_create_rucksack_examples() {
         // Arrange
-        let rucksacks = vec![
+        let rucksacks = [
             "vJrwpWtwJgWrhcsFMMfFFhFp",
             "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
             "PmmdzqPrVvPwwTWBwg",
@@ -138,7 +267,7 @@ mod test_rucksack {
             "ttgJtRGJQctTZtZT",
             "CrZsJsPPZsGzwwsLwLmpwMDw",
         ];
-        let expected_repetitive = vec![
+        let expected_repetitive = [
             'p', // 16
             'L', // 38
             'P', // 42
@@ -152,9 +281,9 @@ mod test_rucksack {
         // Act
         for (rucksack, expected_repetitive_item) in rucksacks.iter().zip(expected_repetitive.iter())
         {
-            let rucksack = Rucksack::new(rucksack);
+            let rucksack = Rucksack::new(rucksack).unwrap();
             // Assert iterative
-            assert_eq!(rucksack.repetitive_item, *expected_repetitive_item);
+            assert_eq!(rucksack.repetitive_item, Some(*expected_repetitive_item));
             total += Rucksack::get_item_priority(Some(*expected_repetitive_item), None);
         }
         // Assert total
@@ -168,34 +297,262 @@ mod test_rucksack {
         assert_eq!(Rucksack::get_item_priority(Some('A'), None), 27);
         assert_eq!(Rucksack::get_item_priority(Some('Z'), None), 52);
     }
+
+    #[test]
+    fn test_weighted_priority() {
+        assert_eq!(Rucksack::new("xpcdpp").unwrap().weighted_priority(), 16 * 3);
+        assert_eq!(
+            Rucksack::new("vJrwpWtwJgWrhcsFMMfFFhFp")
+                .unwrap()
+                .weighted_priority(),
+            16 * 2
+        );
+    }
+
+    #[test]
+    fn test_compartment_sizes() {
+        let rucksack = Rucksack::new("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        assert_eq!(rucksack.compartment_sizes(), (12, 12));
+        // Sets would count 4 and 5 here, as `f` repeats in the first half.
+        assert_eq!(
+            Rucksack::new("ffabcCBADf").unwrap().compartment_sizes(),
+            (5, 5)
+        );
+        // Multi-byte items still count as a single item each.
+        assert_eq!(Rucksack::new("éaéb").unwrap().compartment_sizes(), (2, 2));
+    }
+
+    #[test]
+    fn test_max_item_priority() {
+        assert_eq!(Rucksack::new("aZbcda").unwrap().max_item_priority(), 52);
+        assert_eq!(
+            Rucksack::new("vJrwpWtwJgWrhcsFMMfFFhFp")
+                .unwrap()
+                .max_item_priority(),
+            49 // W
+        );
+    }
+
+    #[test]
+    fn test_line_priority() {
+        assert_eq!(line_priority("vJrwpWtwJgWrhcsFMMfFFhFp"), Ok(16));
+        assert_eq!(
+            line_priority("ab cd"),
+            Err(RucksackError::InteriorWhitespace)
+        );
+        assert_eq!(line_priority("1a1b"), Err(RucksackError::UnknownItem('1')));
+    }
+
+    #[test]
+    fn test_rucksacks_from_reader_unreadable_line() {
+        // Arrange
+        let reader =
+            std::io::Cursor::new(&b"vJrwpWtwJgWrhcsFMMfFFhFp\n\xff\xfe\nPmmdzqPrVvPwwTWBwg\n"[..]);
+
+        // Act
+        let (rucksacks, errors) = rucksacks_from_reader(reader);
+
+        // Assert
+        assert_eq!(rucksacks.len(), 2);
+        assert_eq!(
+            errors,
+            vec![RucksackLineError {
+                line: 2,
+                kind: RucksackError::Io(std::io::ErrorKind::InvalidData)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rucksacks_from_reader() {
+        // Arrange
+        let reader = std::io::Cursor::new("vJrwpWtwJgWrhcsFMMfFFhFp\n\nabc\n");
+
+        // Act
+        let (rucksacks, errors) = rucksacks_from_reader(reader);
+
+        // Assert
+        assert_eq!(rucksacks.len(), 1);
+        assert_eq!(rucksacks[0].repetitive_item, Some('p'));
+        assert_eq!(
+            errors,
+            vec![RucksackLineError {
+                line: 3,
+                kind: RucksackError::OddLength
+            }]
+        );
+    }
+
+    #[test]
+    fn test_create_rucksack_indented() {
+        let rucksack = Rucksack::new("    vJrwpWtwJgWrhcsFMMfFFhFp  ").unwrap();
+        assert_eq!(rucksack.repetitive_item, Some('p'));
+    }
+
+    #[test]
+    fn test_create_rucksack_interior_whitespace() {
+        assert_eq!(
+            Rucksack::new("ab cd").unwrap_err(),
+            RucksackError::InteriorWhitespace
+        );
+    }
+
+    #[test]
+    fn test_create_rucksack_anomalies() {
+        assert_eq!(
+            Rucksack::new("abcd").unwrap_err(),
+            RucksackError::NoCommonItem
+        );
+        assert_eq!(
+            Rucksack::new("abab").unwrap_err(),
+            RucksackError::MultipleCommonItems
+        );
+        assert_eq!(Rucksack::new_lenient("abab").unwrap().repetitive_item, None);
+        assert_eq!(
+            Rucksack::new_lenient("abc").unwrap_err(),
+            RucksackError::OddLength
+        );
+    }
+
+    #[test]
+    fn test_create_rucksack_multibyte_items() {
+        // 4 bytes but 3 items.
+        assert_eq!(
+            Rucksack::new_lenient("aéb").unwrap_err(),
+            RucksackError::OddLength
+        );
+        assert_eq!(
+            Rucksack::new_lenient("éaéb").unwrap().repetitive_item,
+            Some('é')
+        );
+    }
 }
 
 struct GamePart1 {
     rucksacks: Vec<Rucksack>,
 }
 
-trait RucksackGamePart1 {
-    fn new(input_contents: &str) -> Self;
+trait RucksackGamePart1: Sized {
+    fn new(input_contents: &str) -> Result<Self, Vec<RucksackLineError>>;
     fn calculate_repetitive_item_priorities(&self) -> i32;
 }
 
-impl RucksackGamePart1 for GamePart1 {
-    fn new(input_contents: &str) -> Self {
+impl GamePart1 {
+    // Same as `new`, but lets rucksacks that break the repetitive item invariant through.
+    #[allow(dead_code)]
+    fn new_lenient(input_contents: &str) -> Result<Self, Vec<RucksackLineError>> {
         let mut rucksacks = Vec::<Rucksack>::new();
-        for line in input_contents.lines() {
+        let mut errors = Vec::new();
+        for (i, line) in input_contents.lines().enumerate() {
             if line.trim().is_empty() {
                 continue;
             }
-            let rucksack = Rucksack::new(line);
-            rucksacks.push(rucksack);
+            match Rucksack::new_lenient(line) {
+                Ok(rucksack) => rucksacks.push(rucksack),
+                Err(kind) => errors.push(RucksackLineError { line: i + 1, kind }),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(GamePart1 { rucksacks })
+    }
+
+    // The item type present in every single rucksack.
+    // None when there isn't one, and also when more than one item type is shared by all of them.
+    #[allow(dead_code)]
+    fn global_common_item(&self) -> Option<char> {
+        let mut rucksacks = self.rucksacks.iter();
+        let mut common_items: HashSet<char> =
+            rucksacks.next()?.get_items().iter().cloned().collect();
+        for rucksack in rucksacks {
+            common_items.retain(|item| rucksack.get_items().contains(item));
+        }
+
+        if common_items.len() != 1 {
+            return None;
         }
-        GamePart1 { rucksacks }
+        common_items.into_iter().next()
+    }
+
+    // Indices of rucksacks whose compartments don't share exactly one item type.
+    #[allow(dead_code)]
+    fn anomalous_rucksacks(&self) -> Vec<usize> {
+        self.rucksacks
+            .iter()
+            .enumerate()
+            .filter(|(_, rucksack)| rucksack.common_items_count() != 1)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Running total of repetitive item priorities after each rucksack.
+    #[allow(dead_code)]
+    fn cumulative_priorities(&self) -> Vec<i32> {
+        self.rucksacks
+            .iter()
+            .scan(0, |total, rucksack| {
+                *total += Rucksack::get_item_priority(None, Some(rucksack));
+                Some(*total)
+            })
+            .collect()
+    }
+
+    // Same as `calculate_repetitive_item_priorities`, restricted to the rucksacks at `indices`.
+    #[allow(dead_code)]
+    fn priority_sum_for(&self, indices: &[usize]) -> i32 {
+        indices
+            .iter()
+            .map(|&i| Rucksack::get_item_priority(None, Some(&self.rucksacks[i])))
+            .sum()
+    }
+
+    // How many different item types show up across all rucksacks.
+    #[allow(dead_code)]
+    fn distinct_item_types(&self) -> usize {
+        self.rucksacks
+            .iter()
+            .flat_map(|rucksack| rucksack.get_items().iter())
+            .collect::<HashSet<&char>>()
+            .len()
+    }
+
+    // Indices of the rucksacks holding `item` in either compartment.
+    #[allow(dead_code)]
+    fn rucksacks_containing(&self, item: char) -> Vec<usize> {
+        self.rucksacks
+            .iter()
+            .enumerate()
+            .filter(|(_, rucksack)| rucksack.get_items().contains(&item))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Priorities of the distinct item types in each first compartment, summed across rucksacks.
+    #[allow(dead_code)]
+    fn first_compartment_priority_total(&self) -> i32 {
+        self.rucksacks
+            .iter()
+            .flat_map(|rucksack| rucksack.compartments.0.iter())
+            .map(|item| Rucksack::get_item_priority(Some(*item), None))
+            .sum()
+    }
+}
+
+impl RucksackGamePart1 for GamePart1 {
+    // Collects every invalid line, along with its 1-based line number, instead of stopping at the first one.
+    fn new(input_contents: &str) -> Result<Self, Vec<RucksackLineError>> {
+        let (rucksacks, errors) = rucksacks_from_reader(input_contents.as_bytes());
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(GamePart1 { rucksacks })
     }
 
     fn calculate_repetitive_item_priorities(&self) -> i32 {
-        return self.rucksacks.iter().fold(0, |acc, rucksack| {
-            acc + Rucksack::get_item_priority(None, Some(&rucksack))
-        });
+        self.rucksacks.iter().fold(0, |acc, rucksack| {
+            acc + Rucksack::get_item_priority(None, Some(rucksack))
+        })
     }
 }
 
@@ -217,11 +574,195 @@ mod test_game_part1 {
         let expected_total = 157;
 
         // Act
-        let game = GamePart1::new(rucksacks);
+        let game = GamePart1::new(rucksacks).unwrap();
 
         // Assert
         assert_eq!(game.calculate_repetitive_item_priorities(), expected_total);
     }
+
+    #[test]
+    fn test_first_compartment_priority_total() {
+        // Arrange
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw";
+
+        // Act
+        let game = GamePart1::new(rucksacks).unwrap();
+
+        // Assert
+        assert_eq!(game.first_compartment_priority_total(), 1259);
+        assert_eq!(
+            GamePart1::new_lenient("aabc")
+                .unwrap()
+                .first_compartment_priority_total(),
+            1 // a, once
+        );
+    }
+
+    #[test]
+    fn test_cumulative_priorities() {
+        let game = GamePart1::new(
+            "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw",
+        )
+        .unwrap();
+        let cumulative = game.cumulative_priorities();
+        assert_eq!(cumulative, vec![16, 54, 96, 118, 138, 157]);
+        assert_eq!(
+            cumulative.last().copied(),
+            Some(game.calculate_repetitive_item_priorities())
+        );
+        assert!(cumulative.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_priority_sum_for() {
+        let game = GamePart1::new(
+            "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw",
+        )
+        .unwrap();
+        assert_eq!(game.priority_sum_for(&[0, 2]), 16 + 42);
+        assert_eq!(game.priority_sum_for(&[]), 0);
+    }
+
+    #[test]
+    fn test_distinct_item_types() {
+        let game = GamePart1::new(
+            "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw",
+        )
+        .unwrap();
+        assert_eq!(game.distinct_item_types(), 35);
+        assert_eq!(GamePart1::new("").unwrap().distinct_item_types(), 0);
+    }
+
+    #[test]
+    fn test_rucksacks_containing() {
+        let game = GamePart1::new(
+            "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw",
+        )
+        .unwrap();
+        assert_eq!(game.rucksacks_containing('s'), vec![0, 1, 5]);
+        assert_eq!(game.rucksacks_containing('x'), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_global_common_item() {
+        let game = GamePart1::new(
+            "
+    zabczd
+    qzrqst
+    mnozpm",
+        )
+        .unwrap();
+        assert_eq!(game.global_common_item(), Some('z'));
+
+        let game = GamePart1::new(
+            "
+    zabczd
+    zaxzyq",
+        )
+        .unwrap();
+        assert_eq!(game.global_common_item(), None);
+    }
+
+    #[test]
+    fn test_anomalous_rucksacks() {
+        let game = GamePart1::new_lenient(
+            "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    abcdab
+    abcdef
+    PmmdzqPrVvPwwTWBwg",
+        )
+        .unwrap();
+        assert_eq!(game.rucksacks.len(), 4);
+        assert_eq!(game.anomalous_rucksacks(), vec![1, 2]);
+
+        // Structural problems are still rejected.
+        assert_eq!(
+            GamePart1::new_lenient("abab\nabc").err().unwrap(),
+            vec![RucksackLineError {
+                line: 2,
+                kind: RucksackError::OddLength
+            }]
+        );
+    }
+
+    #[test]
+    fn test_collected_rucksack_errors() {
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    abcde
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    abcdef
+
+    abcdab
+    PmmdzqPrVvPwwTWBwg";
+
+        let errors = GamePart1::new(rucksacks).err().unwrap();
+        assert_eq!(
+            errors,
+            vec![
+                RucksackLineError {
+                    line: 3,
+                    kind: RucksackError::OddLength
+                },
+                RucksackLineError {
+                    line: 5,
+                    kind: RucksackError::NoCommonItem
+                },
+                RucksackLineError {
+                    line: 7,
+                    kind: RucksackError::MultipleCommonItems
+                },
+            ]
+        );
+
+        let valid = GamePart1::new(
+            "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg",
+        )
+        .unwrap();
+        assert_eq!(valid.rucksacks.len(), 3);
+
+        assert_eq!(
+            GamePart1::new("aéb").err().unwrap(),
+            vec![RucksackLineError {
+                line: 1,
+                kind: RucksackError::OddLength
+            }]
+        );
+    }
 }
 
 #[derive(Debug)]
@@ -229,24 +770,67 @@ struct ElfGroup {
     elves: Vec<Rucksack>,
 }
 
+#[derive(Debug, PartialEq)]
+enum BadgeError {
+    EmptyGroup,
+    NoBadge,
+    MultipleBadges(usize),
+}
+
 impl ElfGroup {
     fn find_badge(&self) -> char {
+        self.try_find_badge()
+            .unwrap_or_else(|err| panic!("Incorrect badges: {:?}", err))
+    }
+
+    fn try_find_badge(&self) -> Result<char, BadgeError> {
+        if self.elves.is_empty() {
+            return Err(BadgeError::EmptyGroup);
+        }
+        ElfGroup::single_badge(self.common_items())
+    }
+
+    // Every item type shared by all elves of the group, before narrowing it down to a badge.
+    fn common_items(&self) -> HashSet<char> {
         let items: Vec<HashSet<char>> = self
             .elves
             .iter()
             .map(|rucksack| rucksack.get_items().to_owned().into_iter().collect())
             .collect();
+        ElfGroup::intersect_all(items)
+    }
+
+    // Same as `try_find_badge`, but only looking at each elf's second compartment.
+    #[allow(dead_code)]
+    fn try_find_second_compartment_badge(&self) -> Result<char, BadgeError> {
+        if self.elves.is_empty() {
+            return Err(BadgeError::EmptyGroup);
+        }
+        let items: Vec<HashSet<char>> = self
+            .elves
+            .iter()
+            .map(|rucksack| rucksack.compartments.1.to_owned())
+            .collect();
+        ElfGroup::single_badge(ElfGroup::intersect_all(items))
+    }
 
+    // Items present in every set. Empty when there are no sets at all.
+    fn intersect_all(items: Vec<HashSet<char>>) -> HashSet<char> {
         // Too much trouble to fold this one :(
-        let mut badges = items[0].to_owned();
-        for item in items.iter().skip(1) {
-            badges = item.intersection(&badges).cloned().collect();
+        let mut items = items.into_iter();
+        let mut common = items.next().unwrap_or_default();
+        for item in items {
+            common = item.intersection(&common).cloned().collect();
         }
+        common
+    }
 
-        if badges.len() != 1 {
-            panic!("Incorrect badges length: {}", badges.len());
+    fn single_badge(badges: HashSet<char>) -> Result<char, BadgeError> {
+        match badges.len() {
+            0 => Err(BadgeError::NoBadge),
+            1 => Ok(badges.into_iter().next().expect("Invalid badge iter")),
+            length => Err(BadgeError::MultipleBadges(length)),
         }
-        badges.into_iter().next().expect("Invalid badge iter")
     }
 }
 
@@ -259,45 +843,88 @@ mod test_elf_group {
         // Arrange
         let group = ElfGroup {
             elves: vec![
-                Rucksack::new("vJrwpWtwJgWrhcsFMMfFFhFp"),
-                Rucksack::new("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"),
-                Rucksack::new("PmmdzqPrVvPwwTWBwg"),
+                Rucksack::new("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap(),
+                Rucksack::new("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL").unwrap(),
+                Rucksack::new("PmmdzqPrVvPwwTWBwg").unwrap(),
             ],
         };
 
         // Act & assert
         assert_eq!(group.find_badge(), 'r');
     }
+
+    #[test]
+    fn test_common_items() {
+        let group = ElfGroup {
+            elves: vec![
+                Rucksack::new("abcdaf").unwrap(),
+                Rucksack::new("xbyazb").unwrap(),
+                Rucksack::new("bqarsb").unwrap(),
+            ],
+        };
+
+        assert_eq!(group.common_items(), HashSet::from(['a', 'b']));
+        assert_eq!(group.try_find_badge(), Err(BadgeError::MultipleBadges(2)));
+        assert!(ElfGroup { elves: vec![] }.common_items().is_empty());
+    }
+
+    #[test]
+    fn test_try_find_badge_errors() {
+        let no_badge = ElfGroup {
+            elves: vec![
+                Rucksack::new("abcdaf").unwrap(),
+                Rucksack::new("ghijgl").unwrap(),
+            ],
+        };
+        let multiple_badges = ElfGroup {
+            elves: vec![
+                Rucksack::new("abcdaf").unwrap(),
+                Rucksack::new("abcdbf").unwrap(),
+            ],
+        };
+
+        assert_eq!(no_badge.try_find_badge(), Err(BadgeError::NoBadge));
+        assert_eq!(
+            multiple_badges.try_find_badge(),
+            Err(BadgeError::MultipleBadges(5))
+        );
+        assert_eq!(
+            ElfGroup { elves: vec![] }.try_find_badge(),
+            Err(BadgeError::EmptyGroup)
+        );
+    }
 }
 
 struct GamePart2 {
     elf_groups: Vec<ElfGroup>,
 }
 
-trait RucksackGamePart2 {
-    const GROUP_SIZE: usize;
-    fn new(rucksacks: Vec<Rucksack>) -> Self;
+trait RucksackGamePart2: Sized {
+    const DEFAULT_GROUP_SIZE: usize;
+    fn new(rucksacks: Vec<Rucksack>, group_size: usize) -> Result<Self, GameError>;
     fn calculate_group_badges_priorities(&self) -> i32;
 }
 
 impl RucksackGamePart2 for GamePart2 {
-    const GROUP_SIZE: usize = 3;
-
-    fn new(rucksacks: Vec<Rucksack>) -> Self {
-        let mut elf_groups = Vec::<ElfGroup>::new();
-
-        let mut i = 0;
-        while i < rucksacks.len() {
-            let mut group = Vec::new();
-            let mut j = i;
-            while j < i + GamePart2::GROUP_SIZE {
-                group.push(rucksacks[j].to_owned());
-                j += 1;
-            }
-            elf_groups.push(ElfGroup { elves: group });
-            i += GamePart2::GROUP_SIZE;
+    const DEFAULT_GROUP_SIZE: usize = 3;
+
+    // Groups consecutive rucksacks `group_size` at a time.
+    // Fails when the rucksacks can't be split evenly, instead of leaving a partial group behind.
+    fn new(rucksacks: Vec<Rucksack>, group_size: usize) -> Result<Self, GameError> {
+        if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+            return Err(GameError::IncompleteGroup {
+                rucksacks: rucksacks.len(),
+                group_size,
+            });
         }
-        GamePart2 { elf_groups }
+
+        let elf_groups = rucksacks
+            .chunks(group_size)
+            .map(|elves| ElfGroup {
+                elves: elves.to_vec(),
+            })
+            .collect();
+        Ok(GamePart2 { elf_groups })
     }
 
     fn calculate_group_badges_priorities(&self) -> i32 {
@@ -307,6 +934,98 @@ impl RucksackGamePart2 for GamePart2 {
     }
 }
 
+impl GamePart2 {
+    // Same as `calculate_group_badges_priorities`, with badges taken from second compartments only.
+    #[allow(dead_code)]
+    fn calculate_group_badges_priorities_second_compartment(&self) -> i32 {
+        self.elf_groups.iter().fold(0, |acc, elf_group| {
+            let badge = elf_group
+                .try_find_second_compartment_badge()
+                .unwrap_or_else(|err| panic!("Incorrect badges: {:?}", err));
+            acc + Rucksack::get_item_priority(Some(badge), None)
+        })
+    }
+
+    // Every group that doesn't have exactly one badge, along with its index.
+    #[allow(dead_code)]
+    fn validate_groups(&self) -> Result<(), Vec<(usize, BadgeError)>> {
+        let errors = self
+            .elf_groups
+            .iter()
+            .enumerate()
+            .filter_map(|(i, elf_group)| elf_group.try_find_badge().err().map(|err| (i, err)))
+            .collect::<Vec<(usize, BadgeError)>>();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// (part 1 total, part 2 total, part 2 / part 1). The ratio is 0.0 when part 1 totals zero.
+#[allow(dead_code)]
+fn combined_totals(game1: &GamePart1, game2: &GamePart2) -> (i32, i32, f64) {
+    let part1_total = game1.calculate_repetitive_item_priorities();
+    let part2_total = game2.calculate_group_badges_priorities();
+    let ratio = if part1_total == 0 {
+        0.0
+    } else {
+        part2_total as f64 / part1_total as f64
+    };
+    (part1_total, part2_total, ratio)
+}
+
+#[derive(Debug, PartialEq)]
+enum GameError {
+    // The rucksacks can't be split evenly into groups of `group_size`.
+    IncompleteGroup { rucksacks: usize, group_size: usize },
+    Badge { group: usize, error: BadgeError },
+    UnknownItem(char),
+}
+
+// Sums the badge priorities of every group of `group_size` consecutive rucksacks, scored by `scheme`.
+#[allow(dead_code)]
+fn calculate_badges(
+    rucksacks: &[Rucksack],
+    group_size: usize,
+    scheme: &dyn PriorityScheme,
+) -> Result<i32, GameError> {
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        return Err(GameError::IncompleteGroup {
+            rucksacks: rucksacks.len(),
+            group_size,
+        });
+    }
+
+    let mut total = 0;
+    for (group, elves) in rucksacks.chunks(group_size).enumerate() {
+        let elf_group = ElfGroup {
+            elves: elves.to_vec(),
+        };
+        let badge = elf_group
+            .try_find_badge()
+            .map_err(|error| GameError::Badge { group, error })?;
+        total += scheme
+            .priority(badge)
+            .ok_or(GameError::UnknownItem(badge))?;
+    }
+    Ok(total)
+}
+
+// Splits rucksacks into (at most) `parts` contiguous chunks of roughly the same size.
+// Chunk boundaries are aligned to `group_size`, so no elf group is ever split across chunks.
+#[allow(dead_code)]
+fn chunk_rucksacks(rucksacks: &[Rucksack], parts: usize, group_size: usize) -> Vec<&[Rucksack]> {
+    if rucksacks.is_empty() {
+        return Vec::new();
+    }
+    let group_size = group_size.max(1);
+    let groups = rucksacks.len().div_ceil(group_size);
+    let groups_per_chunk = groups.div_ceil(parts.max(1));
+    rucksacks.chunks(groups_per_chunk * group_size).collect()
+}
+
 #[cfg(test)]
 mod test_game_part2 {
     use super::*;
@@ -321,10 +1040,211 @@ mod test_game_part2 {
     ttgJtRGJQctTZtZT
     CrZsJsPPZsGzwwsLwLmpwMDw
     ";
-        let game1 = GamePart1::new(rucksacks);
-        let game2 = GamePart2::new(game1.rucksacks);
+        let game1 = GamePart1::new(rucksacks).unwrap();
+        let game2 = GamePart2::new(game1.rucksacks, GamePart2::DEFAULT_GROUP_SIZE).unwrap();
 
         let total_groups_badges = game2.calculate_group_badges_priorities();
         assert_eq!(total_groups_badges, 70);
     }
+
+    #[test]
+    fn test_configurable_group_size() {
+        // Every rucksack repeats its first item in the second compartment, e.g. `a` in `aZb|caD`.
+        let pairs = "
+    aZbcaD
+    eZfgeh
+    iYjkil
+    mYnomp
+    ";
+        let game1 = GamePart1::new(pairs).unwrap();
+        let game2 = GamePart2::new(game1.rucksacks, 2).unwrap();
+        assert_eq!(game2.elf_groups.len(), 2);
+        assert_eq!(game2.elf_groups[0].try_find_badge(), Ok('Z'));
+        assert_eq!(game2.elf_groups[1].try_find_badge(), Ok('Y'));
+        assert_eq!(game2.calculate_group_badges_priorities(), 52 + 51);
+
+        let quartets = "
+    aZbcaD
+    eZfgeh
+    iZjkil
+    mZnomp
+    ";
+        let game1 = GamePart1::new(quartets).unwrap();
+        let game2 = GamePart2::new(game1.rucksacks, 4).unwrap();
+        assert_eq!(game2.elf_groups.len(), 1);
+        assert_eq!(game2.elf_groups[0].try_find_badge(), Ok('Z'));
+        assert_eq!(game2.calculate_group_badges_priorities(), 52);
+    }
+
+    #[test]
+    fn test_incomplete_group() {
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ";
+        let game1 = GamePart1::new(rucksacks).unwrap();
+        assert!(matches!(
+            GamePart2::new(game1.rucksacks.clone(), 3),
+            Err(GameError::IncompleteGroup {
+                rucksacks: 4,
+                group_size: 3
+            })
+        ));
+        assert!(matches!(
+            GamePart2::new(game1.rucksacks, 0),
+            Err(GameError::IncompleteGroup {
+                rucksacks: 4,
+                group_size: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn test_second_compartment_badges() {
+        // `a` is shared by every elf, but only `Z` by every second compartment.
+        let rucksacks = "
+    abcZde
+    afgZhi
+    jakZlm
+    ";
+        let game1 = GamePart1::new_lenient(rucksacks).unwrap();
+        let game2 = GamePart2::new(game1.rucksacks, GamePart2::DEFAULT_GROUP_SIZE).unwrap();
+
+        assert_eq!(
+            game2.elf_groups[0].try_find_badge(),
+            Err(BadgeError::MultipleBadges(2))
+        );
+        assert_eq!(
+            game2.elf_groups[0].try_find_second_compartment_badge(),
+            Ok('Z')
+        );
+        assert_eq!(
+            game2.calculate_group_badges_priorities_second_compartment(),
+            52
+        );
+    }
+
+    #[test]
+    fn test_combined_totals() {
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw
+    ";
+        let game1 = GamePart1::new(rucksacks).unwrap();
+        let game2 = GamePart2::new(game1.rucksacks.clone(), GamePart2::DEFAULT_GROUP_SIZE).unwrap();
+
+        let (part1_total, part2_total, ratio) = combined_totals(&game1, &game2);
+        assert_eq!((part1_total, part2_total), (157, 70));
+        assert!((ratio - 70.0 / 157.0).abs() < 1e-9);
+
+        let empty1 = GamePart1::new("").unwrap();
+        let empty2 = GamePart2::new(vec![], GamePart2::DEFAULT_GROUP_SIZE).unwrap();
+        assert_eq!(combined_totals(&empty1, &empty2), (0, 0, 0.0));
+    }
+
+    #[test]
+    fn test_validate_groups() {
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    abcdaf
+    ghijgl
+    mnopmr
+    ";
+        let game1 = GamePart1::new(rucksacks).unwrap();
+        let game2 = GamePart2::new(game1.rucksacks, GamePart2::DEFAULT_GROUP_SIZE).unwrap();
+
+        assert_eq!(game2.validate_groups(), Err(vec![(1, BadgeError::NoBadge)]));
+        assert_eq!(
+            GamePart2 {
+                elf_groups: game2.elf_groups.into_iter().take(1).collect()
+            }
+            .validate_groups(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_calculate_badges() {
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw
+    ";
+        let game1 = GamePart1::new(rucksacks).unwrap();
+
+        struct UniformPriorityScheme;
+        impl PriorityScheme for UniformPriorityScheme {
+            fn priority(&self, _item: char) -> Option<i32> {
+                Some(1)
+            }
+        }
+
+        assert_eq!(
+            calculate_badges(&game1.rucksacks, 3, &DefaultPriorityScheme),
+            Ok(70)
+        );
+        assert_eq!(
+            calculate_badges(&game1.rucksacks, 3, &UniformPriorityScheme),
+            Ok(2)
+        );
+        assert_eq!(
+            calculate_badges(&game1.rucksacks, 4, &DefaultPriorityScheme),
+            Err(GameError::IncompleteGroup {
+                rucksacks: 6,
+                group_size: 4
+            })
+        );
+        assert_eq!(
+            calculate_badges(&game1.rucksacks, 1, &DefaultPriorityScheme),
+            Err(GameError::Badge {
+                group: 0,
+                error: BadgeError::MultipleBadges(14)
+            })
+        );
+    }
+
+    #[test]
+    fn test_chunk_rucksacks() {
+        let rucksacks = "
+    vJrwpWtwJgWrhcsFMMfFFhFp
+    jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+    PmmdzqPrVvPwwTWBwg
+    wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+    ttgJtRGJQctTZtZT
+    CrZsJsPPZsGzwwsLwLmpwMDw
+    "
+        .repeat(3);
+        let game1 = GamePart1::new(rucksacks.as_str()).unwrap();
+
+        for parts in 1..=8 {
+            let chunks = chunk_rucksacks(&game1.rucksacks, parts, GamePart2::DEFAULT_GROUP_SIZE);
+            assert!(chunks.len() <= parts);
+            assert_eq!(chunks.iter().map(|chunk| chunk.len()).sum::<usize>(), 18);
+            for chunk in &chunks[..chunks.len() - 1] {
+                assert_eq!(chunk.len() % GamePart2::DEFAULT_GROUP_SIZE, 0);
+            }
+        }
+
+        let chunk_sizes = |parts: usize, group_size: usize| {
+            chunk_rucksacks(&game1.rucksacks[..16], parts, group_size)
+                .iter()
+                .map(|chunk| chunk.len())
+                .collect::<Vec<usize>>()
+        };
+        assert_eq!(chunk_sizes(4, GamePart2::DEFAULT_GROUP_SIZE), vec![6, 6, 4]);
+        assert_eq!(chunk_sizes(3, 4), vec![8, 8]);
+        assert_eq!(chunk_sizes(3, 2), vec![6, 6, 4]);
+        assert!(chunk_rucksacks(&[], 4, GamePart2::DEFAULT_GROUP_SIZE).is_empty());
+    }
 }
@@ -1,11 +1,12 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::read_to_string;
 
 fn main() {
     let input_path = "./input/assignment_pairs.txt";
     let input_contents =
-        read_to_string(input_path).expect(format!("Unable to read {}", input_path).as_str());
+        read_to_string(input_path).unwrap_or_else(|_| panic!("Unable to read {}", input_path));
 
-    let game = Game::new(input_contents.as_str());
+    let game = Game::new(input_contents.as_str(), RangeMode::default());
     println!(
         "Number of fully contained pairs: {}\nNumber of overlapping pairs: {}",
         game.count_fully_contained_pairs(),
@@ -37,10 +38,35 @@ fn main() {
 // In how many assignment pairs does one range fully contain the other?
 type Assignment = (i32, i32);
 
+// How the upper bound of a raw assignment is read:
+// - Inclusive: `2-4` means sections 2, 3 and 4.
+// - Exclusive: `2-4` means sections 2 and 3.
+// Assignments are always stored inclusive, so the overlap/contain logic doesn't need to care.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum RangeMode {
+    #[default]
+    Inclusive,
+    #[allow(dead_code)]
+    Exclusive,
+}
+
+// How many sections an assignment spans, 0 when it's empty (lower > upper).
+fn assignment_size(a: &Assignment) -> i64 {
+    (a.1 as i64 - a.0 as i64 + 1).max(0)
+}
+
 #[derive(Debug, Clone, Copy)]
 struct AssignmentPair(Assignment, Assignment);
 
+// Upper bound given to open-ended assignments like `5-`, i.e. section 5 onward.
+// Sizes can get past i32 from here, so they're all computed as i64.
+const OPEN_UPPER_BOUND: i32 = i32::MAX;
+
+// Upper limit of sections `covered_sections` and `section_coverage` are willing to expand into.
+const MAX_COVERED_SECTIONS: i64 = 100_000;
+
 impl AssignmentPair {
+    #[allow(dead_code)]
     fn new(a: Assignment, b: Assignment) -> Self {
         AssignmentPair(a, b)
     }
@@ -79,11 +105,39 @@ impl AssignmentPair {
     fn either_overlaps(&self) -> bool {
         AssignmentPair::overlap(&self.0, &self.1) || AssignmentPair::overlap(&self.1, &self.0)
     }
+
+    #[allow(dead_code)]
+    fn overlap_size(&self) -> i64 {
+        /* how many sections are assigned to both elves? */
+        let lower = self.0 .0.max(self.1 .0);
+        let upper = self.0 .1.min(self.1 .1);
+        assignment_size(&(lower, upper))
+    }
+
+    #[allow(dead_code)]
+    fn symmetric_difference_size(&self) -> i64 {
+        /* sections assigned to exactly one of the elves, i.e. the union minus the intersection */
+        assignment_size(&self.0) + assignment_size(&self.1) - 2 * self.overlap_size()
+    }
+
+    #[allow(dead_code)]
+    fn covered_sections(&self) -> Option<BTreeSet<i32>> {
+        /* every section assigned to either elf, or None if the ranges add up to more than
+        MAX_COVERED_SECTIONS sections (we'd rather not allocate a set for absurdly large ranges) */
+        if assignment_size(&self.0) + assignment_size(&self.1) > MAX_COVERED_SECTIONS {
+            return None;
+        }
+        Some(
+            (self.0 .0..=self.0 .1)
+                .chain(self.1 .0..=self.1 .1)
+                .collect(),
+        )
+    }
 }
 
-impl Into<bool> for AssignmentPair {
-    fn into(self) -> bool {
-        self.0 .0 >= 0 && self.0 .1 >= 0 && self.1 .0 >= 0 && self.1 .1 >= 0
+impl From<AssignmentPair> for bool {
+    fn from(pair: AssignmentPair) -> bool {
+        pair.0 .0 >= 0 && pair.0 .1 >= 0 && pair.1 .0 >= 0 && pair.1 .1 >= 0
     }
 }
 
@@ -93,6 +147,21 @@ impl PartialEq for AssignmentPair {
     }
 }
 
+impl Eq for AssignmentPair {}
+
+// Lexicographic: first by the first assignment, then by the second one.
+impl Ord for AssignmentPair {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+impl PartialOrd for AssignmentPair {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[cfg(test)]
 mod test_assignment_pairs {
     use super::*;
@@ -138,32 +207,102 @@ mod test_assignment_pairs {
     }
 
     #[test]
-    fn test_overloaded_truthyness_and_eq_operations() {
-        assert_eq!(
-            <AssignmentPair as Into<bool>>::into(AssignmentPair::new((2, 4), (6, 8))),
-            true
-        );
-        assert_eq!(
-            <AssignmentPair as Into<bool>>::into(AssignmentPair::new((0, 0), (0, 0))),
-            true
-        );
+    fn test_overlap_size() {
+        let examples = vec![
+            ((2, 4), (6, 8), 0),
+            ((2, 3), (4, 5), 0),
+            ((5, 7), (7, 9), 1),
+            ((2, 8), (3, 7), 5),
+            ((6, 6), (4, 6), 1),
+            ((2, 6), (4, 8), 3),
+        ];
+
+        for (a, b, expected) in examples {
+            let pair = AssignmentPair::new(a, b);
+            assert_eq!(pair.overlap_size(), expected);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_difference_size() {
+        let examples = vec![
+            ((2, 4), (6, 8), 6), // disjoint: the whole union
+            ((3, 7), (3, 7), 0), // identical
+            ((2, 6), (4, 8), 4), // partial overlap: 2-3 and 7-8
+            ((2, 8), (3, 7), 2), // contained: 2 and 8
+        ];
+
+        for (a, b, expected) in examples {
+            let pair = AssignmentPair::new(a, b);
+            assert_eq!(pair.symmetric_difference_size(), expected);
+        }
+    }
+
+    #[test]
+    fn test_covered_sections() {
         assert_eq!(
-            <AssignmentPair as Into<bool>>::into(AssignmentPair::new((0, 0), (0, -1))),
-            false
+            AssignmentPair::new((2, 4), (6, 8)).covered_sections(),
+            Some(BTreeSet::from([2, 3, 4, 6, 7, 8]))
         );
         assert_eq!(
-            <AssignmentPair as Into<bool>>::into(AssignmentPair::new((0, 0), (-1, 0))),
-            false
+            AssignmentPair::new((2, 6), (4, 8)).covered_sections(),
+            Some(BTreeSet::from([2, 3, 4, 5, 6, 7, 8]))
         );
         assert_eq!(
-            <AssignmentPair as Into<bool>>::into(AssignmentPair::new((-1, 0), (0, 0))),
-            false
+            AssignmentPair::new((0, i32::MAX), (4, 8)).covered_sections(),
+            None
         );
+    }
+
+    #[test]
+    fn test_sort_pairs() {
+        let mut pairs = vec![
+            AssignmentPair::new((5, 7), (7, 9)),
+            AssignmentPair::new((2, 4), (6, 8)),
+            AssignmentPair::new((2, 3), (4, 5)),
+            AssignmentPair::new((2, 4), (6, 8)),
+            AssignmentPair::new((2, 4), (1, 8)),
+        ];
+        pairs.sort();
+        pairs.dedup();
         assert_eq!(
-            <AssignmentPair as Into<bool>>::into(AssignmentPair::new((0, -1), (0, 0))),
-            false
+            pairs,
+            vec![
+                AssignmentPair::new((2, 3), (4, 5)),
+                AssignmentPair::new((2, 4), (1, 8)),
+                AssignmentPair::new((2, 4), (6, 8)),
+                AssignmentPair::new((5, 7), (7, 9)),
+            ]
         );
     }
+
+    #[test]
+    fn test_overloaded_truthyness_and_eq_operations() {
+        assert!(<AssignmentPair as Into<bool>>::into(AssignmentPair::new(
+            (2, 4),
+            (6, 8)
+        )));
+        assert!(<AssignmentPair as Into<bool>>::into(AssignmentPair::new(
+            (0, 0),
+            (0, 0)
+        )));
+        assert!(!<AssignmentPair as Into<bool>>::into(AssignmentPair::new(
+            (0, 0),
+            (0, -1)
+        )));
+        assert!(!<AssignmentPair as Into<bool>>::into(AssignmentPair::new(
+            (0, 0),
+            (-1, 0)
+        )));
+        assert!(!<AssignmentPair as Into<bool>>::into(AssignmentPair::new(
+            (-1, 0),
+            (0, 0)
+        )));
+        assert!(!<AssignmentPair as Into<bool>>::into(AssignmentPair::new(
+            (0, -1),
+            (0, 0)
+        )));
+    }
 }
 
 struct Game {
@@ -171,51 +310,64 @@ struct Game {
 }
 
 impl Game {
-    fn new(raw_pairs: &str) -> Self {
+    fn new(raw_pairs: &str, range_mode: RangeMode) -> Self {
         let pairs = raw_pairs
             .lines()
+            // A line may pack several pairs, separated by `;`
+            .flat_map(|line| line.split(';'))
+            // Skip empty lines (and trailing `;`)
+            .filter(|line| !line.trim().is_empty())
             .map(|line| {
-                let cleaned_line = line.trim();
-                if cleaned_line.is_empty() {
-                    // That's a zero pair.
-                    return AssignmentPair((-1, -1), (-1, -1));
-                }
                 let mut parts = line.trim().split(',');
                 let first_assignment_raw = parts
                     .next()
-                    .expect(format!("Missing first assignment, raw line: {}", line).as_str());
+                    .unwrap_or_else(|| panic!("Missing first assignment, raw line: {}", line));
                 let second_assignment_raw = parts
                     .next()
-                    .expect(format!("Missing second assignment, raw line: {}", line).as_str());
+                    .unwrap_or_else(|| panic!("Missing second assignment, raw line: {}", line));
                 AssignmentPair(
-                    Game::extract_assignment_raw(first_assignment_raw),
-                    Game::extract_assignment_raw(second_assignment_raw),
+                    Game::extract_assignment_raw(first_assignment_raw, range_mode),
+                    Game::extract_assignment_raw(second_assignment_raw, range_mode),
                 )
             })
-            // Filter empty line
-            .filter(|pair| <AssignmentPair as Into<bool>>::into(*pair))
             .collect();
         Game { pairs }
     }
 
-    fn extract_assignment_raw(assignment_raw: &str) -> Assignment {
+    fn from_pairs(pairs: Vec<AssignmentPair>) -> Self {
+        /* skips parsing altogether, pairs are taken as they are */
+        Game { pairs }
+    }
+
+    fn extract_assignment_raw(assignment_raw: &str, range_mode: RangeMode) -> Assignment {
         let mut parts = assignment_raw.split('-');
         let lower = parts
             .next()
             .expect("Missing lower bound")
             .parse::<i32>()
             .expect("Unable to parse lower bound into i32");
-        let upper = parts
-            .next()
-            .expect("Missing upper bound")
+        let upper_raw = parts.next().expect("Missing upper bound");
+        if upper_raw.trim().is_empty() {
+            /* open-ended: there's no upper bound to exclude, whatever the range mode */
+            return (lower, OPEN_UPPER_BOUND);
+        }
+        let upper = upper_raw
             .parse::<i32>()
             .expect("Unable to parse upper bound into i32");
-        (lower, upper)
+        let assignment = match range_mode {
+            RangeMode::Inclusive => (lower, upper),
+            RangeMode::Exclusive => (lower, upper - 1),
+        };
+        /* e.g. `3-3` when exclusive: no sections at all, which every other check would get wrong */
+        if assignment.0 > assignment.1 {
+            panic!("Empty assignment: {}", assignment_raw);
+        }
+        assignment
     }
 
     fn count_fully_contained_pairs(&self) -> usize {
         let mut count = 0;
-        for pair in self.pairs.to_owned() {
+        for pair in &self.pairs {
             if pair.either_contains() {
                 count += 1;
             }
@@ -224,13 +376,168 @@ impl Game {
     }
 
     fn count_overlapping_pairs(&self) -> usize {
-        let mut count = 0;
-        for pair in self.pairs.to_owned() {
-            if pair.either_overlaps() {
-                count += 1;
+        self.overlap_mask()
+            .into_iter()
+            .filter(|&overlaps| overlaps)
+            .count()
+    }
+
+    fn overlap_mask(&self) -> Vec<bool> {
+        /* whether each pair overlaps, in the same order as the pairs */
+        self.pairs
+            .iter()
+            .map(|pair| pair.either_overlaps())
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    fn pairs_by_overlap(&self) -> Vec<(usize, i64)> {
+        /* (index, overlap size) of every pair, largest overlap first and ties by index */
+        let mut pairs = self
+            .pairs
+            .iter()
+            .map(|pair| pair.overlap_size())
+            .enumerate()
+            .collect::<Vec<(usize, i64)>>();
+        pairs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        pairs
+    }
+
+    #[allow(dead_code)]
+    fn bounding_range(&self) -> Option<Assignment> {
+        /* smallest lower bound to largest upper bound, across both halves of every pair */
+        let assignments = self.pairs.iter().flat_map(|pair| [pair.0, pair.1]);
+        let lower = assignments.clone().map(|a| a.0).min()?;
+        let upper = assignments.map(|a| a.1).max()?;
+        Some((lower, upper))
+    }
+
+    #[allow(dead_code)]
+    fn is_fully_covered(&self, bound: Assignment) -> bool {
+        /* walks the merged coverage from the bound's lower end, looking for a section nobody
+        covers. Merged ranges only join on overlap, so adjacent ones (2-4, 5-6) are walked across */
+        let assignments = self
+            .pairs
+            .iter()
+            .flat_map(|pair| [pair.0, pair.1])
+            .collect::<Vec<Assignment>>();
+        let mut next_uncovered = bound.0 as i64;
+        for (lower, upper) in merged_assignments(&assignments) {
+            if next_uncovered > bound.1 as i64 {
+                break;
+            }
+            if (upper as i64) < next_uncovered {
+                continue;
+            }
+            if lower as i64 > next_uncovered {
+                return false;
             }
+            next_uncovered = upper as i64 + 1;
         }
-        count
+        next_uncovered > bound.1 as i64
+    }
+
+    #[allow(dead_code)]
+    fn section_coverage(&self) -> Option<BTreeMap<i32, usize>> {
+        /* how many assignments (either half of any pair) cover each section. Every section gets
+        its own entry, so None if the ranges add up to more than MAX_COVERED_SECTIONS sections */
+        let total_size = self
+            .pairs
+            .iter()
+            .map(|pair| assignment_size(&pair.0) + assignment_size(&pair.1))
+            .sum::<i64>();
+        if total_size > MAX_COVERED_SECTIONS {
+            return None;
+        }
+        let mut coverage = BTreeMap::new();
+        for pair in &self.pairs {
+            for assignment in [pair.0, pair.1] {
+                for section in assignment.0..=assignment.1 {
+                    *coverage.entry(section).or_insert(0) += 1;
+                }
+            }
+        }
+        Some(coverage)
+    }
+
+    #[allow(dead_code)]
+    fn total_overlap(&self) -> i64 {
+        /* sections assigned twice across the whole dataset; disjoint pairs add nothing */
+        self.pairs.iter().map(|pair| pair.overlap_size()).sum()
+    }
+
+    #[allow(dead_code)]
+    fn count_identical_pairs(&self) -> usize {
+        /* both elves were assigned the very same range, the tightest form of containment */
+        self.pairs.iter().filter(|pair| pair.0 == pair.1).count()
+    }
+
+    #[allow(dead_code)]
+    fn translate(&mut self, offset: i32) {
+        /* shifts every section by `offset`; overlaps and containment don't change. Open-ended
+        assignments stay open-ended, and bounds saturate rather than wrap around i32 */
+        for pair in self.pairs.iter_mut() {
+            for assignment in [&mut pair.0, &mut pair.1] {
+                assignment.0 = assignment.0.saturating_add(offset);
+                if assignment.1 != OPEN_UPPER_BOUND {
+                    assignment.1 = assignment.1.saturating_add(offset);
+                }
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    fn clamp_to(&mut self, bound: Assignment) {
+        /* intersects every assignment with `bound`. An assignment entirely outside of it would
+        become empty (lower > upper), and we drop the whole pair rather than keep a half of it */
+        let clamp = |a: Assignment| (a.0.max(bound.0), a.1.min(bound.1));
+        self.pairs = self
+            .pairs
+            .iter()
+            .map(|pair| AssignmentPair::new(clamp(pair.0), clamp(pair.1)))
+            .filter(|pair| pair.0 .0 <= pair.0 .1 && pair.1 .0 <= pair.1 .1)
+            .collect();
+    }
+
+    #[allow(dead_code)]
+    fn count_partial_overlap(&self) -> usize {
+        /* pairs that overlap without either one containing the other. Containing implies
+        overlapping, so this is the overlapping count minus the fully contained count. */
+        self.pairs
+            .iter()
+            .filter(|pair| pair.either_overlaps() && !pair.either_contains())
+            .count()
+    }
+
+    #[allow(dead_code)]
+    fn all_disjoint(&self) -> bool {
+        // `all` short-circuits on the first overlapping pair.
+        self.pairs.iter().all(|pair| !pair.either_overlaps())
+    }
+
+    #[allow(dead_code)]
+    fn all_overlapping(&self) -> bool {
+        self.pairs.iter().all(|pair| pair.either_overlaps())
+    }
+
+    #[allow(dead_code)]
+    fn max_overlap_pair(&self) -> Option<(usize, AssignmentPair, i64)> {
+        let mut max_pair: Option<(usize, AssignmentPair, i64)> = None;
+        for (index, pair) in self.pairs.iter().enumerate() {
+            let overlap_size = pair.overlap_size();
+            // Strictly greater, so ties are kept by their first occurrence.
+            match max_pair {
+                Some((_, _, max_size)) if max_size >= overlap_size => {}
+                _ => max_pair = Some((index, *pair, overlap_size)),
+            }
+        }
+        max_pair
+    }
+}
+
+impl From<Vec<AssignmentPair>> for Game {
+    fn from(pairs: Vec<AssignmentPair>) -> Game {
+        Game::from_pairs(pairs)
     }
 }
 
@@ -247,20 +554,109 @@ mod test_game {
     2-8,3-7
     6-6,4-6
     ";
-        let wanted = vec![
+        let wanted = [
             AssignmentPair::new((2, 4), (6, 8)),
             AssignmentPair::new((2, 3), (4, 5)),
             AssignmentPair::new((5, 7), (7, 9)),
             AssignmentPair::new((2, 8), (3, 7)),
             AssignmentPair::new((6, 6), (4, 6)),
         ];
-        let game = Game::new(examples);
+        let game = Game::new(examples, RangeMode::Inclusive);
         for (pair_got, pair_wanted) in game.pairs.iter().zip(wanted.iter()) {
             assert_eq!(pair_got.0, pair_wanted.0);
             assert_eq!(pair_got.1, pair_wanted.1);
         }
     }
 
+    #[test]
+    fn test_from_pairs() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+        let pairs = vec![
+            AssignmentPair::new((2, 4), (6, 8)),
+            AssignmentPair::new((2, 3), (4, 5)),
+            AssignmentPair::new((5, 7), (7, 9)),
+            AssignmentPair::new((2, 8), (3, 7)),
+            AssignmentPair::new((6, 6), (4, 6)),
+            AssignmentPair::new((2, 6), (4, 8)),
+        ];
+
+        let parsed = Game::new(examples, RangeMode::Inclusive);
+        let game = Game::from_pairs(pairs.clone());
+        assert_eq!(game.pairs, parsed.pairs);
+        assert_eq!(game.count_overlapping_pairs(), 4);
+        assert_eq!(game.count_fully_contained_pairs(), 2);
+        assert_eq!(Game::from(pairs).pairs, parsed.pairs);
+    }
+
+    #[test]
+    fn test_open_ended_ranges() {
+        let examples = "
+    5-,1-10
+    5-,7-9
+    20-,1-10";
+
+        let game = Game::new(examples, RangeMode::Exclusive);
+        assert_eq!(game.pairs[0], AssignmentPair::new((5, i32::MAX), (1, 9)));
+        assert_eq!(game.pairs[0].overlap_size(), 5);
+        assert!(game.pairs[0].either_overlaps());
+        assert!(!game.pairs[0].either_contains());
+        assert!(game.pairs[1].either_contains());
+        assert_eq!(game.overlap_mask(), vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_open_ended_sizes() {
+        let open = i32::MAX as i64;
+
+        let game = Game::new("0-,0-", RangeMode::Inclusive);
+        assert_eq!(game.total_overlap(), open + 1);
+
+        let game = Game::new("5-,7-", RangeMode::Inclusive);
+        assert_eq!(game.pairs[0].symmetric_difference_size(), 2);
+
+        let game = Game::new("5-,5-\n5-,5-", RangeMode::Inclusive);
+        assert_eq!(game.total_overlap(), 2 * (open - 4));
+        assert_eq!(game.section_coverage(), None);
+
+        let mut game = Game::new("5-,1-10", RangeMode::Inclusive);
+        game.translate(100);
+        assert_eq!(
+            game.pairs[0],
+            AssignmentPair::new((105, i32::MAX), (101, 110))
+        );
+        game.translate(-200);
+        assert_eq!(
+            game.pairs[0],
+            AssignmentPair::new((-95, i32::MAX), (-99, -90))
+        );
+    }
+
+    #[test]
+    fn test_multiple_pairs_per_line() {
+        let examples = "
+    2-4,6-8; 1-3,2-5
+    5-7,7-9
+    2-8,3-7;6-6,4-6;";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(
+            game.pairs,
+            vec![
+                AssignmentPair::new((2, 4), (6, 8)),
+                AssignmentPair::new((1, 3), (2, 5)),
+                AssignmentPair::new((5, 7), (7, 9)),
+                AssignmentPair::new((2, 8), (3, 7)),
+                AssignmentPair::new((6, 6), (4, 6)),
+            ]
+        );
+    }
+
     #[test]
     fn test_count_fully_contained_pairs_example() {
         let examples = "
@@ -271,10 +667,123 @@ mod test_game {
     6-6,4-6
     2-6,4-8";
 
-        let game = Game::new(examples);
+        let game = Game::new(examples, RangeMode::Inclusive);
         assert_eq!(game.count_fully_contained_pairs(), 2);
     }
 
+    #[test]
+    fn test_overlap_mask_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(
+            game.overlap_mask(),
+            vec![false, false, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_bounding_range_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(game.bounding_range(), Some((2, 9)));
+        assert_eq!(Game::from_pairs(vec![]).bounding_range(), None);
+    }
+
+    #[test]
+    fn test_is_fully_covered() {
+        let examples = "
+    2-4,3-5
+    6-7,7-9";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert!(game.is_fully_covered((2, 9)));
+        assert!(game.is_fully_covered((4, 8)));
+        assert!(!game.is_fully_covered((1, 9)));
+        assert!(!game.is_fully_covered((2, 10)));
+
+        let with_hole = Game::new("2-4,8-9\n3-5,9-9", RangeMode::Inclusive);
+        assert!(!with_hole.is_fully_covered((2, 9)));
+        assert!(with_hole.is_fully_covered((2, 5)));
+    }
+
+    #[test]
+    fn test_section_coverage() {
+        let examples = "
+    2-4,3-5
+    4-4,7-7";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        let coverage = game.section_coverage().unwrap();
+        assert_eq!(coverage.get(&2), Some(&1));
+        assert_eq!(coverage.get(&4), Some(&3));
+        assert_eq!(coverage.get(&5), Some(&1));
+        assert_eq!(coverage.get(&6), None);
+        assert_eq!(
+            coverage.keys().copied().collect::<Vec<i32>>(),
+            vec![2, 3, 4, 5, 7]
+        );
+    }
+
+    #[test]
+    fn test_total_overlap_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(game.total_overlap(), 10);
+    }
+
+    #[test]
+    fn test_pairs_by_overlap_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        let ranking = game.pairs_by_overlap();
+        assert_eq!(ranking[0], (3, 5));
+        assert_eq!(
+            ranking,
+            vec![(3, 5), (5, 3), (2, 1), (4, 1), (0, 0), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_count_identical_pairs() {
+        let examples = "
+    2-4,6-8
+    3-5,3-5
+    2-8,3-7
+    6-6,6-6";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(game.count_identical_pairs(), 2);
+        assert_eq!(game.count_fully_contained_pairs(), 3);
+    }
+
     #[test]
     fn test_count_overlaps_example() {
         let examples = "
@@ -285,7 +794,254 @@ mod test_game {
     6-6,4-6
     2-6,4-8";
 
-        let game = Game::new(examples);
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(game.count_overlapping_pairs(), 4);
+    }
+
+    #[test]
+    fn test_count_partial_overlap_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(game.count_partial_overlap(), 2);
+        assert_eq!(
+            game.count_partial_overlap(),
+            game.count_overlapping_pairs() - game.count_fully_contained_pairs()
+        );
+    }
+
+    #[test]
+    fn test_translate_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let mut game = Game::new(examples, RangeMode::Inclusive);
+        game.translate(100);
+        assert_eq!(game.pairs[0], AssignmentPair::new((102, 104), (106, 108)));
         assert_eq!(game.count_overlapping_pairs(), 4);
+        assert_eq!(game.count_fully_contained_pairs(), 2);
+    }
+
+    #[test]
+    fn test_clamp_to_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let mut game = Game::new(examples, RangeMode::Inclusive);
+        game.clamp_to((3, 7));
+        assert_eq!(
+            game.pairs,
+            vec![
+                AssignmentPair::new((3, 4), (6, 7)),
+                AssignmentPair::new((3, 3), (4, 5)),
+                AssignmentPair::new((5, 7), (7, 7)),
+                AssignmentPair::new((3, 7), (3, 7)),
+                AssignmentPair::new((6, 6), (4, 6)),
+                AssignmentPair::new((3, 6), (4, 7)),
+            ]
+        );
+
+        let mut game = Game::new("1-2,3-5\n8-9,4-5\n4-4,5-5", RangeMode::Inclusive);
+        game.clamp_to((3, 7));
+        assert_eq!(game.pairs, vec![AssignmentPair::new((4, 4), (5, 5))]);
+    }
+
+    #[test]
+    fn test_max_overlap_pair_example() {
+        let examples = "
+    2-4,6-8
+    2-3,4-5
+    5-7,7-9
+    2-8,3-7
+    6-6,4-6
+    2-6,4-8";
+
+        let game = Game::new(examples, RangeMode::Inclusive);
+        assert_eq!(
+            game.max_overlap_pair(),
+            Some((3, AssignmentPair::new((2, 8), (3, 7)), 5))
+        );
+        assert_eq!(Game::new("", RangeMode::Inclusive).max_overlap_pair(), None);
+    }
+
+    #[test]
+    fn test_all_disjoint_and_all_overlapping() {
+        let disjoint = Game::new(
+            "
+    2-4,6-8
+    2-3,4-5",
+            RangeMode::Inclusive,
+        );
+        let overlapping = Game::new(
+            "
+    5-7,7-9
+    2-8,3-7",
+            RangeMode::Inclusive,
+        );
+        let mixed = Game::new(
+            "
+    2-4,6-8
+    5-7,7-9",
+            RangeMode::Inclusive,
+        );
+
+        assert!(disjoint.all_disjoint());
+        assert!(!disjoint.all_overlapping());
+        assert!(!overlapping.all_disjoint());
+        assert!(overlapping.all_overlapping());
+        assert!(!mixed.all_disjoint());
+        assert!(!mixed.all_overlapping());
+    }
+
+    #[test]
+    fn test_range_modes() {
+        let examples = "
+    5-7,7-9
+    2-6,4-8
+    2-4,4-6";
+
+        let inclusive = Game::new(examples, RangeMode::Inclusive);
+        let exclusive = Game::new(examples, RangeMode::Exclusive);
+        assert_eq!(inclusive.pairs[0], AssignmentPair::new((5, 7), (7, 9)));
+        assert_eq!(exclusive.pairs[0], AssignmentPair::new((5, 6), (7, 8)));
+        assert_eq!(inclusive.count_overlapping_pairs(), 3);
+        assert_eq!(exclusive.count_overlapping_pairs(), 1);
+    }
+
+    #[test]
+    fn test_zero_based_exclusive_range() {
+        let game = Game::new("0-1,0-3", RangeMode::Exclusive);
+        assert_eq!(game.pairs, vec![AssignmentPair::new((0, 0), (0, 2))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Empty assignment: 0-0")]
+    fn test_empty_exclusive_range_at_zero() {
+        Game::new("0-0,1-5", RangeMode::Exclusive);
+    }
+
+    #[test]
+    #[should_panic(expected = "Empty assignment: 3-3")]
+    fn test_empty_exclusive_range() {
+        Game::new("3-3,1-5", RangeMode::Exclusive);
+    }
+}
+
+// Groups assignments that chain together through overlaps, e.g. 2-4, 4-6 and 6-8 all end up in
+// the same cluster even though 2-4 and 6-8 don't overlap each other.
+// Sweeping the assignments by their lower bound is enough: a cluster ends as soon as the next
+// assignment starts after every section covered so far.
+fn coverage_clusters(assignments: &[Assignment]) -> Vec<Vec<usize>> {
+    let mut indices = (0..assignments.len()).collect::<Vec<usize>>();
+    indices.sort_by_key(|&i| assignments[i]);
+
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    let mut cluster_upper = i32::MIN;
+    for i in indices {
+        let (lower, upper) = assignments[i];
+        match clusters.last_mut() {
+            Some(cluster) if lower <= cluster_upper => {
+                cluster.push(i);
+                cluster_upper = cluster_upper.max(upper);
+            }
+            _ => {
+                clusters.push(vec![i]);
+                cluster_upper = upper;
+            }
+        }
+    }
+
+    for cluster in clusters.iter_mut() {
+        cluster.sort();
+    }
+    clusters.sort();
+    clusters
+}
+
+#[cfg(test)]
+mod test_coverage_clusters {
+    use super::*;
+
+    #[test]
+    fn test_chained_clusters() {
+        let assignments = vec![(6, 8), (10, 12), (2, 4), (4, 6)];
+        assert_eq!(
+            coverage_clusters(&assignments),
+            vec![vec![0, 2, 3], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_empty_clusters() {
+        assert!(coverage_clusters(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_merged_assignments() {
+        let assignments = vec![(6, 8), (10, 12), (2, 4), (4, 6)];
+        assert_eq!(merged_assignments(&assignments), vec![(2, 8), (10, 12)]);
+    }
+}
+
+// Sections covered by the assignments, as the fewest disjoint ranges sorted by lower bound.
+// Every cluster overlaps as a chain, so it covers one contiguous range.
+fn merged_assignments(assignments: &[Assignment]) -> Vec<Assignment> {
+    coverage_clusters(assignments)
+        .iter()
+        .map(|cluster| {
+            let lower = cluster.iter().map(|&i| assignments[i].0).min();
+            let upper = cluster.iter().map(|&i| assignments[i].1).max();
+            (
+                lower.expect("Clusters are never empty"),
+                upper.expect("Clusters are never empty"),
+            )
+        })
+        .collect()
+}
+
+// How many assignments cover each covered section, on average. 0.0 when nothing is covered.
+#[allow(dead_code)]
+fn coverage_density(assignments: &[Assignment]) -> f64 {
+    let covered_instances = assignments.iter().map(assignment_size).sum::<i64>();
+    let covered_sections = merged_assignments(assignments)
+        .iter()
+        .map(assignment_size)
+        .sum::<i64>();
+    if covered_sections == 0 {
+        return 0.0;
+    }
+    covered_instances as f64 / covered_sections as f64
+}
+
+#[cfg(test)]
+mod test_coverage_density {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_density() {
+        // 2-4 and 3-5 share 2 sections out of 4, 8-8 stands on its own.
+        let assignments = vec![(2, 4), (3, 5), (8, 8)];
+        assert_eq!(coverage_density(&assignments), 7.0 / 5.0);
+    }
+
+    #[test]
+    fn test_empty_density() {
+        assert_eq!(coverage_density(&[]), 0.0);
     }
 }
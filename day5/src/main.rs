@@ -1,19 +1,21 @@
 use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::read_to_string;
+use std::io::BufRead;
+use std::sync::LazyLock;
 
 fn main() {
     let input_path = "./input/crane-inst.txt";
     let input_contents =
-        read_to_string(input_path).expect(format!("Unable to read {}", input_path).as_str());
+        read_to_string(input_path).unwrap_or_else(|_| panic!("Unable to read {}", input_path));
 
     let game = Game::new(&input_contents);
     let crate_mover_models = vec![CrateMoverModel::Model9000, CrateMoverModel::Model9001];
-    for mover_model in &crate_mover_models {
-        println!(
-            "Message using {:?}: {}\n",
-            mover_model,
-            game.find_message(*mover_model)
-        );
+    let messages = game
+        .find_messages(&crate_mover_models)
+        .unwrap_or_else(|err| panic!("unable to execute moves: {:?}", err));
+    for (mover_model, message) in messages {
+        println!("Message using {:?}: {}\n", mover_model, message);
     }
 }
 
@@ -31,31 +33,146 @@ impl Game {
         }
     }
 
-    fn extract_moves_from_game(moves_map: &str) -> Vec<Move> {
-        let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+    fn extract_moves_from_game(moves_map: &str) -> Result<Vec<Move>, Vec<MoveParseError>> {
+        let moves = Game::extract_numbered_moves(moves_map)?;
+        Ok(moves.into_iter().map(|(_, m)| m).collect())
+    }
+
+    // Same as `extract_moves_from_game`, but keeps the (1-based) line each move came from.
+    // Every malformed line is reported, not just the first one.
+    fn extract_numbered_moves(moves_map: &str) -> Result<Vec<(usize, Move)>, Vec<MoveParseError>> {
+        let (moves, errors) = Game::parse_numbered_moves(moves_map);
+        if errors.is_empty() {
+            Ok(moves)
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Every move that parses, along with every line that doesn't.
+    fn parse_numbered_moves(moves_map: &str) -> (Vec<(usize, Move)>, Vec<MoveParseError>) {
         let mut moves = Vec::new();
-        for move_raw in moves_map.lines() {
-            let captures = re.captures(move_raw);
-            match captures {
-                Some(captures) => {
-                    let amount = &captures[1].parse::<usize>().unwrap();
-                    let from = &captures[2].parse::<usize>().unwrap();
-                    let to = &captures[3].parse::<usize>().unwrap();
-                    moves.push(Move {
-                        amount: *amount,
-                        from: *from,
-                        to: *to,
-                    });
+        let mut errors = Vec::new();
+        for (i, move_raw) in moves_map.lines().enumerate() {
+            // Blank lines and `;` comments are skipped, anything else has to be a move.
+            if move_raw.trim().is_empty() || move_raw.trim_start().starts_with(';') {
+                continue;
+            }
+            match move_raw.parse::<Move>() {
+                Ok(m) => moves.push((i + 1, m)),
+                Err(err) => errors.push(MoveParseError { line: i + 1, ..err }),
+            }
+        }
+        (moves, errors)
+    }
+
+    // Recovers the starting stacks by undoing `moves`, last to first, from `final_stacks`.
+    #[allow(dead_code)]
+    fn reverse_execute(
+        final_stacks: Vec<Vec<char>>,
+        moves: &[Move],
+        mover_model: CrateMoverModel,
+    ) -> Result<Vec<Vec<char>>, MoveError> {
+        let mut stacks = final_stacks;
+        let stack_amount = stacks.len();
+        for m in moves.iter().rev() {
+            let destinations = std::iter::once(m.to)
+                .chain(m.extra_destinations.iter().copied())
+                .collect::<Vec<usize>>();
+            if let Some(&stack) = std::iter::once(&m.from)
+                .chain(destinations.iter())
+                .find(|&&stack| stack == 0 || stack > stack_amount)
+            {
+                return Err(MoveError::StackOutOfRange {
+                    stack,
+                    stacks: stack_amount,
+                });
+            }
+
+            match mover_model {
+                // The block moved as a whole, so it moves back as a whole.
+                CrateMoverModel::Model9001 => {
+                    let available = stacks[m.to - 1].len();
+                    if available < m.amount {
+                        return Err(MoveError::NotEnoughCrates {
+                            stack: m.to,
+                            requested: m.amount,
+                            available,
+                        });
+                    }
+                    let items = stacks[m.to - 1].drain(..m.amount).collect::<Vec<char>>();
+                    stacks[m.from - 1].splice(0..0, items);
+                }
+                // Crates were dealt one at a time (round-robin across destinations), so they're
+                // picked back up one at a time, starting from the last one dealt.
+                CrateMoverModel::Model9000 | CrateMoverModel::Splitter => {
+                    let destinations = match mover_model {
+                        CrateMoverModel::Model9000 => &destinations[..1],
+                        _ => &destinations[..],
+                    };
+                    for i in (0..m.amount).rev() {
+                        let stack = destinations[i % destinations.len()];
+                        if stacks[stack - 1].is_empty() {
+                            return Err(MoveError::NotEnoughCrates {
+                                stack,
+                                requested: m.amount,
+                                available: 0,
+                            });
+                        }
+                        let item = stacks[stack - 1].remove(0);
+                        stacks[m.from - 1].insert(0, item);
+                    }
                 }
-                None => {}
             }
         }
-        moves
+        Ok(stacks)
+    }
+
+    // Every move referencing a stack that doesn't exist, along with the line of the move.
+    // Lines that aren't moves at all are reported as parse errors, in line order.
+    #[allow(dead_code)]
+    fn validate(&self) -> Result<(), Vec<(usize, MoveError)>> {
+        let stacks = Game::extract_stack_contents(self.stacks_raw.as_str()).len();
+        let (moves, parse_errors) = Game::parse_numbered_moves(self.moves_raw.as_str());
+
+        let mut errors = parse_errors
+            .into_iter()
+            .map(|err| (err.line, MoveError::Parse(err)))
+            .collect::<Vec<(usize, MoveError)>>();
+        for (line, m) in moves {
+            let referenced = std::iter::once(m.from)
+                .chain(std::iter::once(m.to))
+                .chain(m.extra_destinations.iter().copied());
+            for stack in referenced.filter(|&stack| stack == 0 || stack > stacks) {
+                errors.push((line, MoveError::StackOutOfRange { stack, stacks }));
+            }
+        }
+        errors.sort_by_key(|(line, _)| *line);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
     fn extract_stack_contents(stack_raw: &str) -> Vec<Vec<char>> {
-        let (stack_indices, stack_ids_row) = extract_stack_indicies(stack_raw).unwrap();
-        let stack_amount = stack_indices.len();
+        // The index row sits one character in (` 1   2 `), so on its own the stacks block may
+        // only lose whole columns of indentation.
+        let stack_raw = dedent(stack_raw, 4);
+        let stack_raw = stack_raw.as_str();
+        let (stack_amount, stack_ids_row) = match extract_stack_indicies(stack_raw) {
+            Some((stack_indices, stack_ids_row)) => (stack_indices.len(), stack_ids_row),
+            // No index row: every 4 characters of the widest row make up a stack (the last
+            // one is missing its trailing space), and every line is a row of crates.
+            None => {
+                let widest_row = stack_raw
+                    .lines()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                (widest_row.div_ceil(4), stack_raw.lines().count())
+            }
+        };
         let mut stacks = Vec::new();
         stacks.resize(stack_amount, Vec::new());
 
@@ -63,17 +180,18 @@ impl Game {
             if row == stack_ids_row {
                 break;
             }
-            let mut j = 0;
-            for stack_index in 0..stack_amount {
-                // We have, at most N stacks.
-                // Each stack consumes 4 characters.
-                // Therefore, to find out which stack we are in, we can divide the index by 4.
-                let stack_item_raw = line.chars().skip(j).take(4).collect::<String>();
+            // We have, at most N stacks.
+            // Each stack consumes 4 characters.
+            // Therefore, to find out which stack we are in, we can divide the index by 4.
+            // Positions are counted in chars (not bytes), so multi-byte labels like `[🦀]`
+            // still take a single position.
+            let cells = line.chars().collect::<Vec<char>>();
+            for (stack, cell) in stacks.iter_mut().zip(cells.chunks(4)) {
+                let stack_item_raw = cell.iter().collect::<String>();
                 if !stack_item_raw.trim().is_empty() {
                     let stack_item = extract_stack_item(stack_item_raw);
-                    stacks[stack_index].push(stack_item);
+                    stack.push(stack_item);
                 }
-                j += 4;
             }
         }
         stacks
@@ -85,43 +203,701 @@ impl Game {
             .skip_while(|line| line.trim().is_empty()) // Skips any potential empty lines at the start
             .collect::<Vec<&str>>() // Collect it into a Vec<&str>
             .join("\n"); // join into a single string
+        let cleaned_input = dedent(cleaned_input.as_str(), 1); // Drop indentation shared by every line
         let re = Regex::new(r"\n{2,}").unwrap();
 
         let game = re
             .split(cleaned_input.as_str())
             .map(|line| line.to_string())
             .collect::<Vec<String>>();
+
+        // Either block may come first: the stacks are the one with crates or the index row.
+        let is_stacks_block =
+            |block: &str| block.contains('[') || extract_stack_indicies(block).is_some();
+        if !is_stacks_block(&game[0]) && is_stacks_block(&game[1]) {
+            return (game[1].clone(), game[0].clone());
+        }
         (game[0].clone(), game[1].clone())
     }
 
-    fn execute_moves(&self, mover_model: CrateMoverModel) -> CrateMover {
-        let stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
-        let moves = Game::extract_moves_from_game(self.moves_raw.as_str());
-        let stacks_collection = CrateMover::new(stacks, mover_model);
-        let new_stacks = stacks_collection.execute_moves(moves);
-        CrateMover {
-            stacks: new_stacks,
-            model: mover_model,
-        }
+    #[allow(dead_code)]
+    fn execute_moves(&self, mover_model: CrateMoverModel) -> CrateMover {
+        let stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
+        let moves = Game::extract_moves_from_game(self.moves_raw.as_str())
+            .unwrap_or_else(|err| panic!("unable to parse moves: {:?}", err));
+        let stacks_collection = CrateMover::new(stacks, mover_model);
+        let new_stacks = stacks_collection
+            .execute_moves(moves)
+            .unwrap_or_else(|err| panic!("unable to execute moves: {:?}", err));
+        CrateMover::new(new_stacks, mover_model)
+    }
+
+    // Same as `extract_moves_from_game`, with the first malformed line reported as a `MoveError`.
+    fn parsed_moves(&self) -> Result<Vec<Move>, MoveError> {
+        Game::extract_moves_from_game(self.moves_raw.as_str()).map_err(|errors| {
+            let first = errors.into_iter().next();
+            MoveError::Parse(first.expect("Parsing only fails with some error"))
+        })
+    }
+
+    // Stacks after applying only the first `move_index` moves (clamped to the amount of moves).
+    #[allow(dead_code)]
+    fn state_after(
+        &self,
+        mover_model: CrateMoverModel,
+        move_index: usize,
+    ) -> Result<Vec<Vec<char>>, MoveError> {
+        let mut stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
+        let moves = self.parsed_moves()?;
+        let crate_mover = CrateMover::new(stacks.clone(), mover_model);
+        for m in moves.iter().take(move_index) {
+            crate_mover.apply_move(&mut stacks, m)?;
+        }
+        Ok(stacks)
+    }
+
+    // Top crate of the (1-based) `stack` after every move, None whenever the stack is empty.
+    #[allow(dead_code)]
+    fn top_history(
+        &self,
+        mover_model: CrateMoverModel,
+        stack: usize,
+    ) -> Result<Vec<Option<char>>, MoveError> {
+        let mut stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
+        if stack == 0 || stack > stacks.len() {
+            return Err(MoveError::StackOutOfRange {
+                stack,
+                stacks: stacks.len(),
+            });
+        }
+        let moves = self.parsed_moves()?;
+        let crate_mover = CrateMover::new(stacks.clone(), mover_model);
+        moves
+            .iter()
+            .map(|m| {
+                crate_mover.apply_move(&mut stacks, m)?;
+                Ok(stacks[stack - 1].first().copied())
+            })
+            .collect()
+    }
+
+    // (0-based) indices of the moves that flip the order of the crates they carry under `mover_model`.
+    #[allow(dead_code)]
+    fn move_reversals(&self, mover_model: CrateMoverModel) -> Vec<usize> {
+        let moves = Game::extract_moves_from_game(self.moves_raw.as_str())
+            .unwrap_or_else(|err| panic!("unable to parse moves: {:?}", err));
+        moves
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| match mover_model {
+                // One crate at a time: any block of crates lands upside down.
+                CrateMoverModel::Model9000 => m.amount > 1,
+                CrateMoverModel::Model9001 => false,
+                // Upside down as soon as some destination gets more than one crate.
+                CrateMoverModel::Splitter => m.amount > 1 + m.extra_destinations.len(),
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Crates that stay in place through every move. Moves only ever take crates from the top,
+    // so whatever sits below the lowest height a stack ever drops to is never touched.
+    #[allow(dead_code)]
+    fn untouched_crate_count(&self, mover_model: CrateMoverModel) -> Result<usize, MoveError> {
+        let mut stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
+        let moves = self.parsed_moves()?;
+        let crate_mover = CrateMover::new(stacks.clone(), mover_model);
+        let mut lowest_heights = stacks
+            .iter()
+            .map(|stack| stack.len())
+            .collect::<Vec<usize>>();
+        for m in &moves {
+            crate_mover.apply_move(&mut stacks, m)?;
+            for (lowest_height, stack) in lowest_heights.iter_mut().zip(stacks.iter()) {
+                *lowest_height = (*lowest_height).min(stack.len());
+            }
+        }
+        Ok(lowest_heights.iter().sum())
+    }
+
+    // Final (1-based stack, 0-based depth from the top) of the crate labeled `label`.
+    // Labels may repeat, in which case the crate followed is the first one found in the
+    // starting layout, scanning stacks left to right and each stack top to bottom.
+    #[allow(dead_code)]
+    fn locate_crate(
+        &self,
+        mover_model: CrateMoverModel,
+        label: char,
+    ) -> Result<Option<(usize, usize)>, MoveError> {
+        let mut stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
+        let moves = self.parsed_moves()?;
+
+        // Swap the crate we follow for a label nobody else uses, so it can be told apart.
+        let tracked = ('\u{E000}'..='\u{F8FF}')
+            .find(|c| !stacks.iter().flatten().any(|item| item == c))
+            .expect("Unable to find an unused label");
+        let Some(item) = stacks.iter_mut().flatten().find(|item| **item == label) else {
+            return Ok(None);
+        };
+        *item = tracked;
+
+        let crate_mover = CrateMover::new(stacks.clone(), mover_model);
+        for m in &moves {
+            crate_mover.apply_move(&mut stacks, m)?;
+        }
+        Ok(stacks.iter().enumerate().find_map(|(i, stack)| {
+            stack
+                .iter()
+                .position(|item| *item == tracked)
+                .map(|depth| (i + 1, depth))
+        }))
+    }
+
+    #[allow(dead_code)]
+    fn final_heights(&self, mover_model: CrateMoverModel) -> Vec<usize> {
+        self.execute_moves(mover_model)
+            .stacks
+            .iter()
+            .map(|stack| stack.len())
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    fn find_message(&self, mover_model: CrateMoverModel) -> String {
+        let stack_collection = self.execute_moves(mover_model);
+        let mut message: Vec<char> = Vec::new();
+        for stack in stack_collection.stacks {
+            message.push(stack[0]);
+        }
+        let t = message.iter().collect::<String>();
+        t
+    }
+
+    // Fewest single-crate moves turning the starting stacks into ones whose tops spell `target`.
+    // The search is breadth-first over every configuration, which grows exponentially with the
+    // moves, so it's only attempted on small games (up to `MAX_SEARCH_STACKS` stacks holding
+    // `MAX_SEARCH_CRATES` crates). It gives up (None) past `MAX_SEARCH_MOVES` moves away or
+    // `MAX_SEARCH_STATES` configurations seen.
+    #[allow(dead_code)]
+    fn min_moves_to_message(&self, target: &str) -> Option<usize> {
+        const MAX_SEARCH_MOVES: usize = 8;
+        const MAX_SEARCH_STATES: usize = 100_000;
+        const MAX_SEARCH_STACKS: usize = 5;
+        const MAX_SEARCH_CRATES: usize = 10;
+
+        let start = Game::extract_stack_contents(self.stacks_raw.as_str());
+        let crates = start.iter().map(|stack| stack.len()).sum::<usize>();
+        if start.len() > MAX_SEARCH_STACKS || crates > MAX_SEARCH_CRATES {
+            return None;
+        }
+        if target.chars().count() != start.len() {
+            return None;
+        }
+        // Every label on top has to come from a crate, so they can't outnumber the crates.
+        let mut available = HashMap::new();
+        for &item in start.iter().flatten() {
+            *available.entry(item).or_insert(0) += 1;
+        }
+        for label in target.chars() {
+            match available.get_mut(&label) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => return None,
+            }
+        }
+
+        let target = target.chars().map(Some).collect::<Vec<Option<char>>>();
+        let tops = |stacks: &Vec<Vec<char>>| {
+            stacks
+                .iter()
+                .map(|stack| stack.first().copied())
+                .collect::<Vec<Option<char>>>()
+        };
+
+        let mut seen = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((stacks, moves)) = queue.pop_front() {
+            if tops(&stacks) == target {
+                return Some(moves);
+            }
+            if moves == MAX_SEARCH_MOVES {
+                continue;
+            }
+            for from in 0..stacks.len() {
+                if stacks[from].is_empty() {
+                    continue;
+                }
+                for to in (0..stacks.len()).filter(|&to| to != from) {
+                    let mut next = stacks.clone();
+                    let crate_moved = next[from].remove(0);
+                    next[to].insert(0, crate_moved);
+                    if seen.len() >= MAX_SEARCH_STATES {
+                        return None;
+                    }
+                    if seen.insert(next.clone()) {
+                        queue.push_back((next, moves + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Parses stacks and moves only once, then runs every model from that shared state.
+    fn find_messages(
+        &self,
+        mover_models: &[CrateMoverModel],
+    ) -> Result<Vec<(CrateMoverModel, String)>, MoveError> {
+        let stacks = Game::extract_stack_contents(self.stacks_raw.as_str());
+        let moves = self.parsed_moves()?;
+        mover_models
+            .iter()
+            .map(|&mover_model| {
+                let mut model_stacks = stacks.clone();
+                let crate_mover = CrateMover::new(stacks.clone(), mover_model);
+                for m in &moves {
+                    crate_mover.apply_move(&mut model_stacks, m)?;
+                }
+                // Empty stacks have nothing on top, so they don't add to the message.
+                let message = model_stacks
+                    .iter()
+                    .filter_map(|stack| stack.first())
+                    .collect::<String>();
+                Ok((mover_model, message))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_full_game {
+    use super::*;
+
+    #[test]
+    fn test_game_execute_moves_model9000() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        let message = game.find_message(CrateMoverModel::Model9000);
+        assert_eq!(message, "CMZ");
+    }
+
+    #[test]
+    fn test_game_execute_moves_model9001() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        let message = game.find_message(CrateMoverModel::Model9001);
+        assert_eq!(message, "MCD");
+    }
+
+    #[test]
+    fn test_game_find_messages() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        let messages = game
+            .find_messages(&[CrateMoverModel::Model9000, CrateMoverModel::Model9001])
+            .unwrap()
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect::<Vec<String>>();
+        assert_eq!(messages, vec!["CMZ", "MCD"]);
+    }
+
+    #[test]
+    fn test_game_oversized_move_is_reported() {
+        let example = "
+    [A]    
+    [B] [C]
+     1   2 
+
+    move 1 from 2 to 1
+    move 3 from 2 to 1";
+
+        let game = Game::new(example);
+        let model = CrateMoverModel::Model9000;
+        let err = MoveError::NotEnoughCrates {
+            stack: 2,
+            requested: 3,
+            available: 0,
+        };
+
+        assert_eq!(game.find_messages(&[model]).err(), Some(err.clone()));
+        assert_eq!(
+            game.state_after(model, 1),
+            Ok(vec![vec!['C', 'A', 'B'], vec![]])
+        );
+        assert_eq!(game.state_after(model, 2), Err(err.clone()));
+        assert_eq!(game.top_history(model, 1), Err(err.clone()));
+        assert_eq!(game.untouched_crate_count(model), Err(err.clone()));
+        assert_eq!(game.locate_crate(model, 'A'), Err(err));
+    }
+
+    #[test]
+    fn test_game_malformed_move_is_reported() {
+        let example = "
+    [A]    
+    [B] [C]
+     1   2 
+
+    move 1 from 2 to 1
+    move x";
+
+        let game = Game::new(example);
+        let model = CrateMoverModel::Model9000;
+        let err = MoveError::Parse(MoveParseError {
+            line: 2,
+            raw: "move x".to_string(),
+        });
+
+        assert_eq!(game.find_messages(&[model]).err(), Some(err.clone()));
+        assert_eq!(game.state_after(model, 1), Err(err.clone()));
+        assert_eq!(game.top_history(model, 1), Err(err.clone()));
+        assert_eq!(game.untouched_crate_count(model), Err(err.clone()));
+        assert_eq!(game.locate_crate(model, 'A'), Err(err));
+    }
+
+    #[test]
+    fn test_game_find_messages_empty_stack() {
+        let example = "
+    [A]    
+    [B] [C]
+     1   2 
+
+    move 1 from 2 to 1";
+
+        let game = Game::new(example);
+        let messages = game.find_messages(&[CrateMoverModel::Model9000]).unwrap();
+        assert_eq!(messages[0].1, "C");
+    }
+
+    #[test]
+    fn test_game_min_moves_to_message() {
+        let tiny_example = "
+    [A]    
+    [B] [C]
+     1   2 
+
+    move 1 from 1 to 2";
+
+        let game = Game::new(tiny_example);
+
+        assert_eq!(game.min_moves_to_message("AC"), Some(0));
+        assert_eq!(game.min_moves_to_message("BA"), Some(1));
+        // Two stacks always keep their crates in the same combined order, so C can't top B.
+        assert_eq!(game.min_moves_to_message("CB"), None);
+        assert_eq!(game.min_moves_to_message("DD"), None);
+        assert_eq!(game.min_moves_to_message("A"), None);
+        assert_eq!(game.min_moves_to_message("AA"), None);
+    }
+
+    #[test]
+    fn test_game_min_moves_to_message_bounded() {
+        // J sits under 9 other crates, way past the moves limit. The search has to give up
+        // on its own rather than explore every configuration up to that limit.
+        let example = "
+    [A]                
+    [B]                
+    [C]                
+    [D]                
+    [E]                
+    [F]                
+    [G]                
+    [H]                
+    [I]                
+    [J]                
+     1   2   3   4   5 
+
+    move 1 from 1 to 2";
+
+        let game = Game::new(example);
+        assert_eq!(game.min_moves_to_message("JABCD"), None);
+
+        let too_many_stacks = "
+    [A] [B] [C] [D] [E] [F]
+     1   2   3   4   5   6 
+
+    move 1 from 1 to 2";
+        assert_eq!(
+            Game::new(too_many_stacks).min_moves_to_message("ABCDEF"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_game_move_reversals() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(game.move_reversals(CrateMoverModel::Model9000), vec![1, 2]);
+        assert_eq!(
+            game.move_reversals(CrateMoverModel::Model9001),
+            Vec::<usize>::new()
+        );
+        assert_eq!(game.move_reversals(CrateMoverModel::Splitter), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_game_validate() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    ; stack 4 doesn't exist
+    move 3 from 1 to 4
+    move two from 1 o 3
+    move 2 from 0 to 1";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(
+            game.validate(),
+            Err(vec![
+                (
+                    3,
+                    MoveError::StackOutOfRange {
+                        stack: 4,
+                        stacks: 3
+                    }
+                ),
+                (
+                    4,
+                    MoveError::Parse(MoveParseError {
+                        line: 4,
+                        raw: "move two from 1 o 3".to_string(),
+                    })
+                ),
+                (
+                    5,
+                    MoveError::StackOutOfRange {
+                        stack: 0,
+                        stacks: 3
+                    }
+                ),
+            ])
+        );
+
+        let valid_example = "
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1";
+        assert_eq!(Game::new(valid_example).validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_game_reverse_execute() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+        let start = Game::extract_stack_contents(game.stacks_raw.as_str());
+        let moves = Game::extract_moves_from_game(game.moves_raw.as_str()).unwrap();
+
+        for model in [CrateMoverModel::Model9000, CrateMoverModel::Model9001] {
+            let final_stacks = game.execute_moves(model).stacks;
+            assert_eq!(
+                Game::reverse_execute(final_stacks, &moves, model),
+                Ok(start.clone())
+            );
+        }
+
+        let split = vec![Move {
+            amount: 3,
+            from: 2,
+            to: 1,
+            extra_destinations: vec![3],
+        }];
+        let split_stacks = CrateMover::new(start.clone(), CrateMoverModel::Splitter)
+            .execute_moves(vec![Move {
+                amount: 3,
+                from: 2,
+                to: 1,
+                extra_destinations: vec![3],
+            }])
+            .unwrap();
+        assert_eq!(
+            Game::reverse_execute(split_stacks, &split, CrateMoverModel::Splitter),
+            Ok(start)
+        );
+
+        assert_eq!(
+            Game::reverse_execute(vec![vec![], vec!['A']], &split, CrateMoverModel::Model9001),
+            Err(MoveError::StackOutOfRange {
+                stack: 3,
+                stacks: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_game_untouched_crate_count() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        // Only P, at the bottom of the third stack, never moves.
+        assert_eq!(
+            game.untouched_crate_count(CrateMoverModel::Model9000),
+            Ok(1)
+        );
+        assert_eq!(
+            game.untouched_crate_count(CrateMoverModel::Model9001),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn test_game_locate_crate() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(
+            game.locate_crate(CrateMoverModel::Model9000, 'D'),
+            Ok(Some((3, 2)))
+        );
+        assert_eq!(
+            game.locate_crate(CrateMoverModel::Model9001, 'D'),
+            Ok(Some((3, 0)))
+        );
+        assert_eq!(
+            game.locate_crate(CrateMoverModel::Model9000, 'M'),
+            Ok(Some((2, 0)))
+        );
+        assert_eq!(game.locate_crate(CrateMoverModel::Model9000, 'X'), Ok(None));
+    }
+
+    #[test]
+    fn test_game_final_heights() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(
+            game.final_heights(CrateMoverModel::Model9000),
+            vec![1, 1, 4]
+        );
+        assert_eq!(
+            game.final_heights(CrateMoverModel::Model9001),
+            vec![1, 1, 4]
+        );
     }
 
-    fn find_message(&self, mover_model: CrateMoverModel) -> String {
-        let stack_collection = self.execute_moves(mover_model);
-        let mut message: Vec<char> = Vec::new();
-        for stack in stack_collection.stacks {
-            message.push(stack[0].into());
-        }
-        let t = message.iter().collect::<String>();
-        t
-    }
-}
+    #[test]
+    fn test_game_state_after() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
 
-#[cfg(test)]
-mod test_full_game {
-    use super::*;
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+
+        let game = Game::new(full_example);
+        let model = CrateMoverModel::Model9000;
+
+        assert_eq!(
+            game.state_after(model, 0),
+            Ok(Game::extract_stack_contents(game.stacks_raw.as_str()))
+        );
+        assert_eq!(
+            game.state_after(model, 1),
+            Ok(vec![vec!['D', 'N', 'Z'], vec!['C', 'M'], vec!['P']])
+        );
+        assert_eq!(
+            game.state_after(model, 4),
+            Ok(game.execute_moves(model).stacks)
+        );
+        assert_eq!(
+            game.state_after(model, 100),
+            Ok(game.execute_moves(model).stacks)
+        );
+    }
 
     #[test]
-    fn test_game_execute_moves_model9000() {
+    fn test_game_top_history() {
         let full_example = "
         [D]    
     [N] [C]    
@@ -135,17 +911,69 @@ mod test_full_game {
 
         let game = Game::new(full_example);
 
-        let message = game.find_message(CrateMoverModel::Model9000);
-        assert_eq!(message, "CMZ");
+        assert_eq!(
+            game.top_history(CrateMoverModel::Model9000, 1),
+            Ok(vec![Some('D'), None, Some('M'), Some('C')])
+        );
+        assert_eq!(
+            game.top_history(CrateMoverModel::Model9001, 1),
+            Ok(vec![Some('D'), None, Some('C'), Some('M')])
+        );
+        for stack in [0, 4] {
+            assert_eq!(
+                game.top_history(CrateMoverModel::Model9000, stack),
+                Err(MoveError::StackOutOfRange { stack, stacks: 3 })
+            );
+        }
     }
 
     #[test]
-    fn test_game_execute_moves_model9001() {
+    fn test_game_non_letter_labels() {
+        let full_example = "
+    [8]    
+    [#] [A]
+    [@] [B]
+    1   2  
+
+    move 2 from 1 to 2
+    move 1 from 2 to 1";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(
+            Game::extract_stack_contents(game.stacks_raw.as_str()),
+            vec![vec!['8', '#', '@'], vec!['A', 'B']]
+        );
+        assert_eq!(game.find_message(CrateMoverModel::Model9000), "#8");
+        assert_eq!(game.find_message(CrateMoverModel::Model9001), "8#");
+    }
+
+    #[test]
+    fn test_game_emoji_labels() {
+        let full_example = "
+    [🦀]    
+    [🎄] [A]
+    [é] [B]
+    1   2  
+
+    move 2 from 1 to 2";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(
+            Game::extract_stack_contents(game.stacks_raw.as_str()),
+            vec![vec!['🦀', '🎄', 'é'], vec!['A', 'B']]
+        );
+        assert_eq!(game.find_message(CrateMoverModel::Model9000), "é🎄");
+        assert_eq!(game.find_message(CrateMoverModel::Model9001), "é🦀");
+    }
+
+    #[test]
+    fn test_game_missing_index_row() {
         let full_example = "
         [D]    
     [N] [C]    
     [Z] [M] [P]
-    1   2   3 
 
     move 1 from 2 to 1
     move 3 from 1 to 3
@@ -154,8 +982,11 @@ mod test_full_game {
 
         let game = Game::new(full_example);
 
-        let message = game.find_message(CrateMoverModel::Model9001);
-        assert_eq!(message, "MCD");
+        assert_eq!(
+            Game::extract_stack_contents(game.stacks_raw.as_str()),
+            vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']]
+        );
+        assert_eq!(game.find_message(CrateMoverModel::Model9000), "CMZ");
     }
 
     #[test]
@@ -178,12 +1009,34 @@ mod test_full_game {
         assert_eq!(stacks_raw, "    [S] [A]\n[B] [C]\n    [8] [C]");
         assert_eq!(moves_raw, "    move 1 from 4 to 2\n    ");
     }
+
+    #[test]
+    fn test_game_moves_before_stacks() {
+        let full_example = "
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2
+
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 ";
+
+        let game = Game::new(full_example);
+
+        assert_eq!(game.find_message(CrateMoverModel::Model9000), "CMZ");
+        assert_eq!(game.find_message(CrateMoverModel::Model9001), "MCD");
+    }
 }
 
 fn extract_stack_indicies(input: &str) -> Option<(Vec<&str>, usize)> {
     for (i, line) in input.lines().enumerate() {
+        // Crates may be labeled with digits too (e.g. `[8]`), so the index row is the one
+        // made of nothing but digits.
+        let is_index_row = line.chars().all(|c| c.is_numeric() || c.is_whitespace());
         let stacks: Vec<&str> = line.matches(char::is_numeric).collect();
-        if stacks.len() > 0 {
+        if is_index_row && !stacks.is_empty() {
             return Some((stacks, i));
         }
         if line.is_empty() && i > 0 {
@@ -194,14 +1047,106 @@ fn extract_stack_indicies(input: &str) -> Option<(Vec<&str>, usize)> {
     None
 }
 
+// A crate is a `[X]` chunk, where the label `X` can be any single character: letters,
+// but also digits and symbols like `[8]`, `[#]` or `[@]`. Labels are kept as-is all
+// the way to `find_message`.
 fn extract_stack_item(stack_raw: String) -> char {
     let mut stack = stack_raw.chars();
     let stack_item = stack
         .nth(1)
-        .expect(format!("unable to extract stack item: {:?}", stack_raw).as_str());
+        .unwrap_or_else(|| panic!("unable to extract stack item: {:?}", stack_raw));
     stack_item
 }
 
+fn extract_move_amount(amount_raw: &str) -> Option<usize> {
+    // Some generators spell out small amounts, e.g. "move one from 2 to 1".
+    match amount_raw {
+        "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        "four" => Some(4),
+        "five" => Some(5),
+        "six" => Some(6),
+        "seven" => Some(7),
+        "eight" => Some(8),
+        "nine" => Some(9),
+        _ => amount_raw.parse::<usize>().ok(),
+    }
+}
+
+fn dedent(input: &str, step: usize) -> String {
+    // Stacks are column-aligned, so indentation shared by every line has to go before
+    // we start slicing lines into 4-character chunks.
+    // Only a multiple of `step` is dropped, so that blanks standing for empty stacks survive.
+    let indentation = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+    let indentation = indentation - indentation % step;
+    input
+        .lines()
+        .map(|line| line.chars().skip(indentation).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[allow(dead_code)]
+fn render_stacks(stacks: &[Vec<char>]) -> String {
+    // The inverse of `extract_stack_contents`: each stack is a 3-character column
+    // (`[X]`, or blanks when the stack is shorter) separated by a single space.
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let mut rows = Vec::new();
+    for row in 0..height {
+        let cells = stacks
+            .iter()
+            .map(|stack| {
+                // Stacks are stored top first, so shorter stacks start further down.
+                let padding = height - stack.len();
+                if row < padding {
+                    "   ".to_string()
+                } else {
+                    format!("[{}]", stack[row - padding])
+                }
+            })
+            .collect::<Vec<String>>();
+        rows.push(cells.join(" "));
+    }
+    let indices = (1..=stacks.len())
+        .map(|index| format!(" {} ", index))
+        .collect::<Vec<String>>();
+    rows.push(indices.join(" "));
+    rows.join("\n")
+}
+
+// (1-based) stacks that differ between `a` and `b`, each with its crates listed top first,
+// e.g. `"ZNDP != DNZP"`. A stack missing on one side counts as an empty one.
+#[allow(dead_code)]
+fn diff_stacks(a: &[Vec<char>], b: &[Vec<char>]) -> Vec<(usize, String)> {
+    let empty = Vec::new();
+    (0..a.len().max(b.len()))
+        .filter_map(|i| {
+            let stack_a = a.get(i).unwrap_or(&empty);
+            let stack_b = b.get(i).unwrap_or(&empty);
+            if stack_a == stack_b {
+                return None;
+            }
+            let describe = |stack: &Vec<char>| {
+                if stack.is_empty() {
+                    "(empty)".to_string()
+                } else {
+                    stack.iter().collect::<String>()
+                }
+            };
+            Some((
+                i + 1,
+                format!("{} != {}", describe(stack_a), describe(stack_b)),
+            ))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test_games_utils {
     use super::*;
@@ -219,11 +1164,206 @@ mod test_games_utils {
         assert_eq!(stacks[1], vec!['D', 'C', 'M', 'B']);
         assert_eq!(stacks[2], vec!['P', 'C']);
     }
+
+    #[test]
+    fn test_extract_stack_contents_empty_first_stack() {
+        let stacks = Game::extract_stack_contents("    [D]\n    [C]\n 1   2 ");
+        assert_eq!(stacks, vec![vec![], vec!['D', 'C']]);
+
+        let indented = "
+            [D]
+            [C]
+         1   2 ";
+        assert_eq!(
+            Game::extract_stack_contents(indented),
+            vec![vec![], vec!['D', 'C']]
+        );
+
+        let game = Game::new("    [D]\n    [C]\n 1   2 \n\nmove 1 from 2 to 1");
+        assert_eq!(
+            game.execute_moves(CrateMoverModel::Model9000).stacks,
+            vec![vec!['D'], vec!['C']]
+        );
+    }
+
+    #[test]
+    fn test_render_stacks() {
+        let stacks = vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']];
+        assert_eq!(
+            render_stacks(&stacks),
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+        assert_eq!(render_stacks(&[vec![], vec!['A']]), "    [A]\n 1   2 ");
+    }
+
+    #[test]
+    fn test_render_stacks_round_trip() {
+        let examples = vec![
+            vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']],
+            vec![vec![], vec!['A', 'B']],
+            vec![vec![], vec!['A'], vec![]],
+        ];
+        for stacks in examples {
+            let rendered = render_stacks(&stacks);
+            assert_eq!(Game::extract_stack_contents(&rendered), stacks);
+        }
+    }
+
+    #[test]
+    fn test_diff_stacks() {
+        let full_example = "
+        [D]    
+    [N] [C]    
+    [Z] [M] [P]
+    1   2   3 
+
+    move 1 from 2 to 1
+    move 3 from 1 to 3
+    move 2 from 2 to 1
+    move 1 from 1 to 2";
+        let game = Game::new(full_example);
+        let model9000 = game.execute_moves(CrateMoverModel::Model9000).stacks;
+        let model9001 = game.execute_moves(CrateMoverModel::Model9001).stacks;
+
+        assert_eq!(
+            diff_stacks(&model9000, &model9001),
+            vec![
+                (1, "C != M".to_string()),
+                (2, "M != C".to_string()),
+                (3, "ZNDP != DNZP".to_string()),
+            ]
+        );
+        assert_eq!(diff_stacks(&model9000, &model9000), vec![]);
+        assert_eq!(
+            diff_stacks(&[vec!['A']], &[vec!['A'], vec!['B']]),
+            vec![(2, "(empty) != B".to_string())]
+        );
+    }
 }
+#[derive(Debug, PartialEq)]
 struct Move {
     amount: usize,
     from: usize,
     to: usize,
+    // Only the Splitter model makes use of destinations other than `to`.
+    extra_destinations: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum MoveError {
+    // A move refers to the (1-based) `stack`, but there are only `stacks` of them.
+    StackOutOfRange {
+        stack: usize,
+        stacks: usize,
+    },
+    // A move would pile the (1-based) `stack` higher than `capacity` crates.
+    StackOverflow {
+        stack: usize,
+        capacity: usize,
+    },
+    Parse(MoveParseError),
+    // A move takes `requested` crates out of the (1-based) `stack`, which only holds `available`.
+    NotEnoughCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+    // The (1-based) `line` couldn't be read out of a streamed moves section.
+    Io {
+        line: usize,
+        kind: std::io::ErrorKind,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MoveParseError {
+    // Line within the moves section, always 1 when parsing a single move.
+    line: usize,
+    raw: String,
+}
+
+// Compiled once, as moves are parsed one line at a time.
+static MOVE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^move (\d+|one|two|three|four|five|six|seven|eight|nine) from (\d+) to (\d+|\[[\d,\s]+\])$",
+    )
+    .unwrap()
+});
+
+impl std::str::FromStr for Move {
+    type Err = MoveParseError;
+
+    fn from_str(move_raw: &str) -> Result<Self, Self::Err> {
+        let err = || MoveParseError {
+            line: 1,
+            raw: move_raw.to_string(),
+        };
+        // Anchored on both ends: anything around the move makes the whole line malformed.
+        let captures = MOVE_RE.captures(move_raw.trim()).ok_or_else(err)?;
+
+        let amount = extract_move_amount(&captures[1]).ok_or_else(err)?;
+        let from = captures[2].parse::<usize>().map_err(|_| err())?;
+        // Either a single destination, or a list of them e.g. `[2,3]`.
+        let destinations = captures[3]
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|to| to.trim().parse::<usize>().map_err(|_| err()))
+            .collect::<Result<Vec<usize>, MoveParseError>>()?;
+        let (&to, extra_destinations) = destinations.split_first().ok_or_else(err)?;
+        Ok(Move {
+            amount,
+            from,
+            to,
+            extra_destinations: extra_destinations.to_vec(),
+        })
+    }
+}
+
+#[test]
+fn test_parse_move() {
+    assert_eq!(
+        "move 3 from 1 to 2".parse::<Move>(),
+        Ok(Move {
+            amount: 3,
+            from: 1,
+            to: 2,
+            extra_destinations: vec![],
+        })
+    );
+    assert_eq!(
+        "  move 3 from 1 to 2  ".parse::<Move>().map(|m| m.amount),
+        Ok(3)
+    );
+    assert_eq!(
+        "move 3 from one to 2".parse::<Move>(),
+        Err(MoveParseError {
+            line: 1,
+            raw: "move 3 from one to 2".to_string(),
+        })
+    );
+
+    let too_large = "99999999999999999999999";
+    for move_raw in [
+        "move 1 from 1 to [2,]".to_string(),
+        "move 1 from 1 to [,]".to_string(),
+        "remove 1 from 2 to 3".to_string(),
+        "mmove 1 from 2 to 3".to_string(),
+        "move 1 from 2 to 3 to 4".to_string(),
+        "move 1 from 2 to 3x".to_string(),
+        "move 12 from 1 to 2oops".to_string(),
+        "move 1 from 2 to 3 please".to_string(),
+        format!("move {} from 1 to 2", too_large),
+        format!("move 1 from {} to 2", too_large),
+        format!("move 1 from 1 to [2, {}]", too_large),
+    ] {
+        assert_eq!(
+            move_raw.parse::<Move>(),
+            Err(MoveParseError {
+                line: 1,
+                raw: move_raw.clone(),
+            })
+        );
+    }
 }
 
 #[test]
@@ -238,30 +1378,35 @@ move 1 from 7 to 8";
             amount: 3,
             from: 4,
             to: 6,
+            extra_destinations: vec![],
         },
         Move {
             amount: 1,
             from: 5,
             to: 8,
+            extra_destinations: vec![],
         },
         Move {
             amount: 3,
             from: 7,
             to: 3,
+            extra_destinations: vec![],
         },
         Move {
             amount: 4,
             from: 5,
             to: 7,
+            extra_destinations: vec![],
         },
         Move {
             amount: 1,
             from: 7,
             to: 8,
+            extra_destinations: vec![],
         },
     ];
 
-    let moves = Game::extract_moves_from_game(example);
+    let moves = Game::extract_moves_from_game(example).unwrap();
     for (m, w) in moves.into_iter().zip(want) {
         assert_eq!(m.amount, w.amount, "amounts did not match");
         assert_eq!(m.from, w.from, "from did not match");
@@ -269,48 +1414,241 @@ move 1 from 7 to 8";
     }
 }
 
+#[test]
+fn test_extract_moves_from_game_spelled_out() {
+    let moves =
+        Game::extract_moves_from_game("move three from 1 to 2\nmove 10 from 2 to 3").unwrap();
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves[0].amount, 3);
+    assert_eq!(moves[0].from, 1);
+    assert_eq!(moves[0].to, 2);
+    assert_eq!(moves[1].amount, 10);
+}
+
+#[test]
+fn test_extract_moves_from_game_comments() {
+    let example = "; first, unstack 2
+move 1 from 2 to 1
+    ; then move everything to 3
+move 3 from 1 to 3";
+    let moves = Game::extract_moves_from_game(example).unwrap();
+    assert_eq!(moves.len(), 2);
+    assert_eq!(moves[1].amount, 3);
+
+    let err = Game::extract_moves_from_game("; comment\nmove 1 from 2 to 1\nmov 3 from 1 to 3")
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        vec![MoveParseError {
+            line: 3,
+            raw: "mov 3 from 1 to 3".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_extract_moves_from_game_multiple_destinations() {
+    let moves = Game::extract_moves_from_game("move 4 from 1 to [2, 3]").unwrap();
+    assert_eq!(moves[0].amount, 4);
+    assert_eq!(moves[0].from, 1);
+    assert_eq!(moves[0].to, 2);
+    assert_eq!(moves[0].extra_destinations, vec![3]);
+}
+
+#[test]
+fn test_extract_moves_from_game_malformed() {
+    let err = Game::extract_moves_from_game(
+        "move 1 from 2 to 1\nmove a few from 1 to 2\n; fine\nmove 1 from 1 to 2\nmove 1 fro 1 to 2",
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        err,
+        vec![
+            MoveParseError {
+                line: 2,
+                raw: "move a few from 1 to 2".to_string(),
+            },
+            MoveParseError {
+                line: 5,
+                raw: "move 1 fro 1 to 2".to_string(),
+            },
+        ]
+    );
+}
+
 #[derive(Debug, Copy, Clone)]
 enum CrateMoverModel {
     Model9000,
     Model9001,
+    // Moves crates one at a time, dealing them round-robin across every destination of a move.
+    #[allow(dead_code)]
+    Splitter,
+}
+
+// What to do with a move asking for more crates than its source stack holds.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+enum MoveSaturation {
+    // Refuse the move with a `NotEnoughCrates` error.
+    #[default]
+    Strict,
+    // Move whatever is left on the stack.
+    #[allow(dead_code)]
+    Clamp,
 }
 
 struct CrateMover {
     stacks: Vec<Vec<char>>,
     model: CrateMoverModel,
+    // Maximum height of every stack. None for stacks as tall as they need to be.
+    capacity: Option<usize>,
+    saturation: MoveSaturation,
 }
 
 impl CrateMover {
     fn new(stacks: Vec<Vec<char>>, model: CrateMoverModel) -> CrateMover {
-        CrateMover { stacks, model }
+        CrateMover {
+            stacks,
+            model,
+            capacity: None,
+            saturation: MoveSaturation::default(),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn with_capacity(
+        stacks: Vec<Vec<char>>,
+        model: CrateMoverModel,
+        capacity: usize,
+    ) -> CrateMover {
+        CrateMover {
+            capacity: Some(capacity),
+            ..CrateMover::new(stacks, model)
+        }
+    }
+
+    #[allow(dead_code)]
+    fn with_saturation(self, saturation: MoveSaturation) -> CrateMover {
+        CrateMover { saturation, ..self }
     }
 
-    fn execute_moves(&self, moves: Vec<Move>) -> Vec<Vec<char>> {
+    fn execute_moves(&self, moves: Vec<Move>) -> Result<Vec<Vec<char>>, MoveError> {
         let mut stacks_new = self.stacks.clone();
         for m in moves {
-            let mut items_to_move = stacks_new[m.from - 1]
-                .drain(..m.amount)
-                .collect::<Vec<char>>();
-            println!(
-                "items_to_move: {:?}; &self.stacks: {:?}",
-                items_to_move,
-                &self.stacks[m.from - 1]
-            );
-            match self.model {
-                CrateMoverModel::Model9000 => {
-                    for item in items_to_move {
-                        stacks_new[m.to - 1].insert(0, item);
-                    }
+            self.apply_move(&mut stacks_new, &m)?;
+        }
+        Ok(stacks_new)
+    }
+
+    // Same as `execute_moves`, but moves are parsed and applied one line at a time.
+    #[allow(dead_code)]
+    fn execute_from_reader<R: BufRead>(&self, reader: R) -> Result<Vec<Vec<char>>, MoveError> {
+        let mut stacks_new = self.stacks.clone();
+        for (i, move_raw) in reader.lines().enumerate() {
+            let move_raw = move_raw.map_err(|err| MoveError::Io {
+                line: i + 1,
+                kind: err.kind(),
+            })?;
+            if move_raw.trim().is_empty() || move_raw.trim_start().starts_with(';') {
+                continue;
+            }
+            let m = move_raw
+                .parse::<Move>()
+                .map_err(|err| MoveError::Parse(MoveParseError { line: i + 1, ..err }))?;
+            self.apply_move(&mut stacks_new, &m)?;
+        }
+        Ok(stacks_new)
+    }
+
+    // Executes a single move, making sure every stack it refers to exists, the source holds
+    // enough crates (as far as the saturation mode cares) and no stack ends up above capacity.
+    fn apply_move(&self, stacks: &mut [Vec<char>], m: &Move) -> Result<(), MoveError> {
+        let referenced = std::iter::once(m.from)
+            .chain(std::iter::once(m.to))
+            .chain(m.extra_destinations.iter().copied());
+        for stack in referenced {
+            if stack == 0 || stack > stacks.len() {
+                return Err(MoveError::StackOutOfRange {
+                    stack,
+                    stacks: stacks.len(),
+                });
+            }
+        }
+
+        let available = stacks[m.from - 1].len();
+        let amount = if m.amount <= available {
+            m.amount
+        } else {
+            match self.saturation {
+                MoveSaturation::Strict => {
+                    return Err(MoveError::NotEnoughCrates {
+                        stack: m.from,
+                        requested: m.amount,
+                        available,
+                    });
                 }
-                CrateMoverModel::Model9001 => {
-                    items_to_move.reverse();
-                    for item in items_to_move {
-                        stacks_new[m.to - 1].insert(0, item);
-                    }
+                MoveSaturation::Clamp => available,
+            }
+        };
+
+        if let Some(capacity) = self.capacity {
+            // Heights once the move is done, worked out before touching any crate. Only
+            // destinations grow, so they're the only stacks that can overflow.
+            let destinations = match self.model {
+                CrateMoverModel::Splitter => std::iter::once(m.to)
+                    .chain(m.extra_destinations.iter().copied())
+                    .collect::<Vec<usize>>(),
+                CrateMoverModel::Model9000 | CrateMoverModel::Model9001 => vec![m.to],
+            };
+            let mut heights = stacks
+                .iter()
+                .map(|stack| stack.len())
+                .collect::<Vec<usize>>();
+            heights[m.from - 1] -= amount;
+            for i in 0..amount {
+                heights[destinations[i % destinations.len()] - 1] += 1;
+            }
+            for &stack in &destinations {
+                if heights[stack - 1] > capacity {
+                    return Err(MoveError::StackOverflow { stack, capacity });
+                }
+            }
+        }
+
+        let m = Move {
+            amount,
+            from: m.from,
+            to: m.to,
+            extra_destinations: m.extra_destinations.clone(),
+        };
+        self.execute_move(stacks, &m);
+        Ok(())
+    }
+
+    fn execute_move(&self, stacks: &mut [Vec<char>], m: &Move) {
+        let mut items_to_move = stacks[m.from - 1].drain(..m.amount).collect::<Vec<char>>();
+        match self.model {
+            CrateMoverModel::Model9000 => {
+                for item in items_to_move {
+                    stacks[m.to - 1].insert(0, item);
+                }
+            }
+            CrateMoverModel::Model9001 => {
+                items_to_move.reverse();
+                for item in items_to_move {
+                    stacks[m.to - 1].insert(0, item);
+                }
+            }
+            CrateMoverModel::Splitter => {
+                let destinations = std::iter::once(&m.to)
+                    .chain(m.extra_destinations.iter())
+                    .collect::<Vec<&usize>>();
+                for (i, item) in items_to_move.into_iter().enumerate() {
+                    stacks[destinations[i % destinations.len()] - 1].insert(0, item);
                 }
             }
         }
-        stacks_new
     }
 }
 
@@ -328,10 +1666,11 @@ mod test_crate_mover {
             amount: 1,
             from: 2,
             to: 1,
+            extra_destinations: vec![],
         }];
         let expected = vec![vec!['D', 'N', 'Z'], vec!['C', 'M'], vec!['P']];
 
-        let new_stack = game.execute_moves(moves);
+        let new_stack = game.execute_moves(moves).unwrap();
         assert_eq!(new_stack, expected);
     }
 
@@ -346,16 +1685,18 @@ mod test_crate_mover {
                 amount: 1,
                 from: 2,
                 to: 1,
+                extra_destinations: vec![],
             },
             Move {
                 amount: 3,
                 from: 1,
                 to: 3,
+                extra_destinations: vec![],
             },
         ];
         let expected = vec![vec![], vec!['C', 'M'], vec!['Z', 'N', 'D', 'P']];
 
-        let new_stack = game.execute_moves(moves);
+        let new_stack = game.execute_moves(moves).unwrap();
         assert_eq!(new_stack, expected);
     }
 
@@ -370,21 +1711,24 @@ mod test_crate_mover {
                 amount: 1,
                 from: 2,
                 to: 1,
+                extra_destinations: vec![],
             },
             Move {
                 amount: 3,
                 from: 1,
                 to: 3,
+                extra_destinations: vec![],
             },
             Move {
                 amount: 2,
                 from: 2,
                 to: 1,
+                extra_destinations: vec![],
             },
         ];
         let expected = vec![vec!['M', 'C'], vec![], vec!['Z', 'N', 'D', 'P']];
 
-        let new_stack = game.execute_moves(moves);
+        let new_stack = game.execute_moves(moves).unwrap();
         assert_eq!(new_stack, expected);
     }
 
@@ -399,26 +1743,208 @@ mod test_crate_mover {
                 amount: 1,
                 from: 2,
                 to: 1,
+                extra_destinations: vec![],
             },
             Move {
                 amount: 3,
                 from: 1,
                 to: 3,
+                extra_destinations: vec![],
             },
             Move {
                 amount: 2,
                 from: 2,
                 to: 1,
+                extra_destinations: vec![],
             },
             Move {
                 amount: 1,
                 from: 1,
                 to: 2,
+                extra_destinations: vec![],
             },
         ];
         let expected = vec![vec!['C'], vec!['M'], vec!['Z', 'N', 'D', 'P']];
 
-        let new_stack = game.execute_moves(moves);
+        let new_stack = game.execute_moves(moves).unwrap();
+        assert_eq!(new_stack, expected);
+    }
+
+    #[test]
+    fn test_execute_moves_splitter() {
+        let game = CrateMover::new(
+            vec![vec!['A', 'B', 'C', 'D'], vec!['N'], vec![]],
+            CrateMoverModel::Splitter,
+        );
+        let moves = vec![Move {
+            amount: 4,
+            from: 1,
+            to: 2,
+            extra_destinations: vec![3],
+        }];
+        let expected = vec![vec![], vec!['C', 'A', 'N'], vec!['D', 'B']];
+
+        let new_stack = game.execute_moves(moves).unwrap();
         assert_eq!(new_stack, expected);
     }
+
+    #[test]
+    fn test_execute_from_reader() {
+        let stacks = vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']];
+        let moves_raw = "move 1 from 2 to 1
+move 3 from 1 to 3
+
+; comments are fine too
+move 2 from 2 to 1
+move 1 from 1 to 2";
+        let game = CrateMover::new(stacks, CrateMoverModel::Model9001);
+
+        let streamed = game.execute_from_reader(std::io::Cursor::new(moves_raw));
+        let batched = game.execute_moves(Game::extract_moves_from_game(moves_raw).unwrap());
+        assert_eq!(streamed, batched);
+        assert_eq!(
+            streamed.unwrap(),
+            vec![vec!['M'], vec!['C'], vec!['D', 'N', 'Z', 'P']]
+        );
+
+        assert_eq!(
+            game.execute_from_reader(std::io::Cursor::new("move 1 from 2 to 1\nmove x")),
+            Err(MoveError::Parse(MoveParseError {
+                line: 2,
+                raw: "move x".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_execute_from_failing_reader() {
+        struct FailingReader;
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disconnected"))
+            }
+        }
+
+        let game = CrateMover::new(vec![vec!['A'], vec!['B']], CrateMoverModel::Model9000);
+        let reader =
+            std::io::Read::chain(std::io::Cursor::new("move 1 from 2 to 1\n"), FailingReader);
+        assert_eq!(
+            game.execute_from_reader(std::io::BufReader::new(reader)),
+            Err(MoveError::Io {
+                line: 2,
+                kind: std::io::ErrorKind::Other
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_moves_out_of_range() {
+        let game = CrateMover::new(vec![vec!['A', 'B']], CrateMoverModel::Splitter);
+        for (move_raw, stack) in [
+            ("move 1 from 0 to 1", 0),
+            ("move 1 from 1 to 5", 5),
+            ("move 1 from 1 to [1, 2]", 2),
+        ] {
+            assert_eq!(
+                game.execute_moves(vec![move_raw.parse::<Move>().unwrap()]),
+                Err(MoveError::StackOutOfRange { stack, stacks: 1 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_moves_saturation() {
+        let stacks = vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']];
+        let moves = || {
+            vec![Move {
+                amount: 5,
+                from: 2,
+                to: 3,
+                extra_destinations: vec![],
+            }]
+        };
+
+        let strict = CrateMover::new(stacks.clone(), CrateMoverModel::Model9001);
+        assert_eq!(
+            strict.execute_moves(moves()),
+            Err(MoveError::NotEnoughCrates {
+                stack: 2,
+                requested: 5,
+                available: 3
+            })
+        );
+
+        let clamp = CrateMover::new(stacks, CrateMoverModel::Model9001)
+            .with_saturation(MoveSaturation::Clamp);
+        assert_eq!(
+            clamp.execute_moves(moves()),
+            Ok(vec![vec!['N', 'Z'], vec![], vec!['D', 'C', 'M', 'P']])
+        );
+    }
+
+    #[test]
+    fn test_execute_moves_capacity() {
+        let stacks = vec![vec!['N', 'Z'], vec!['D', 'C', 'M'], vec!['P']];
+        let moves = || {
+            vec![
+                Move {
+                    amount: 1,
+                    from: 2,
+                    to: 1,
+                    extra_destinations: vec![],
+                },
+                Move {
+                    amount: 2,
+                    from: 1,
+                    to: 3,
+                    extra_destinations: vec![],
+                },
+            ]
+        };
+
+        let game = CrateMover::with_capacity(stacks.clone(), CrateMoverModel::Model9000, 3);
+        assert_eq!(
+            game.execute_moves(moves()),
+            Ok(vec![vec!['Z'], vec!['C', 'M'], vec!['N', 'D', 'P']])
+        );
+
+        let game = CrateMover::with_capacity(stacks.clone(), CrateMoverModel::Model9000, 2);
+        assert_eq!(
+            game.execute_moves(moves()),
+            Err(MoveError::StackOverflow {
+                stack: 1,
+                capacity: 2
+            })
+        );
+
+        // A refused move leaves every stack as it was.
+        let mut partial = stacks.clone();
+        assert_eq!(
+            game.apply_move(&mut partial, &moves()[0]),
+            Err(MoveError::StackOverflow {
+                stack: 1,
+                capacity: 2
+            })
+        );
+        assert_eq!(partial, stacks);
+
+        let splitter = CrateMover::with_capacity(
+            vec![vec!['A', 'B', 'C'], vec![], vec!['D', 'E']],
+            CrateMoverModel::Splitter,
+            2,
+        );
+        assert_eq!(
+            splitter.execute_moves(vec![Move {
+                amount: 3,
+                from: 1,
+                to: 2,
+                extra_destinations: vec![3],
+            }]),
+            Err(MoveError::StackOverflow {
+                stack: 3,
+                capacity: 2
+            })
+        );
+    }
 }